@@ -0,0 +1,36 @@
+// Full-screen color effects, see `src/screen_effect.rs`.
+
+#import bevy_core_pipeline::fullscreen_vertex_shader::FullscreenVertexOutput
+
+@group(0) @binding(0) var screen_texture: texture_2d<f32>;
+@group(0) @binding(1) var texture_sampler: sampler;
+
+struct ScreenEffect {
+    tint: vec4<f32>,
+    vignette: f32,
+    vignette_tint: f32,
+    desaturation: f32,
+    contrast: f32,
+}
+@group(0) @binding(2) var<uniform> settings: ScreenEffect;
+
+@fragment
+fn fragment(in: FullscreenVertexOutput) -> @location(0) vec4<f32> {
+    var color = textureSample(screen_texture, texture_sampler, in.uv).rgb;
+
+    // Desaturate towards the luminance of the pixel.
+    let luminance = dot(color, vec3(0.2126, 0.7152, 0.0722));
+    color = mix(color, vec3(luminance), settings.desaturation);
+
+    // Grade the colors: push contrast around mid grey, then multiply by the tint.
+    color = (color - 0.5) * settings.contrast + 0.5;
+    color = mix(color, color * settings.tint.rgb, settings.tint.a);
+
+    // Darken the corners, bleeding the tint into the vignette.
+    let offset = (in.uv - 0.5) * 2.0;
+    let edge = smoothstep(0.4, 1.4, length(offset));
+    let vignette_color = settings.tint.rgb * 0.15 * settings.vignette_tint;
+    color = mix(color, vignette_color, edge * settings.vignette);
+
+    return vec4(clamp(color, vec3(0.0), vec3(1.0)), 1.0);
+}
@@ -52,6 +52,16 @@ impl ResourceHandles {
     pub fn is_all_done(&self) -> bool {
         self.waiting.is_empty()
     }
+
+    /// Fraction of requested [`Asset`]s that have finished loading, between 0 and 1.
+    pub fn progress(&self) -> f32 {
+        let total = self.waiting.len() + self.finished.len();
+        if total == 0 {
+            1.0
+        } else {
+            self.finished.len() as f32 / total as f32
+        }
+    }
 }
 
 fn load_resource_assets(world: &mut World) {
@@ -1,10 +1,18 @@
-use bevy::prelude::*;
+use bevy::{audio::Volume, prelude::*};
+
+use crate::settings::Settings;
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(
         Update,
-        apply_global_volume.run_if(resource_changed::<GlobalVolume>),
+        (
+            apply_master_volume.run_if(resource_changed::<Settings>),
+            apply_global_volume
+                .run_if(resource_changed::<GlobalVolume>.or(resource_changed::<Settings>)),
+        )
+            .chain(),
     );
+    app.add_observer(apply_channel_volume_on_play);
 }
 
 /// An organizational marker component that should be added to a spawned [`AudioPlayer`] if it's in the
@@ -33,12 +41,58 @@ pub fn sound_effect(handle: Handle<AudioSource>) -> impl Bundle {
     (AudioPlayer(handle), PlaybackSettings::DESPAWN, SoundEffect)
 }
 
+/// Volume of the channel an audio entity belongs to, from the [`Settings`].
+fn channel_volume(settings: &Settings, music: bool, sound_effect: bool) -> Volume {
+    let linear = if music {
+        settings.music_volume
+    } else if sound_effect {
+        settings.sfx_volume
+    } else {
+        1.0
+    };
+    Volume::Linear(linear)
+}
+
+fn apply_master_volume(settings: Res<Settings>, mut global_volume: ResMut<GlobalVolume>) {
+    let volume = Volume::Linear(settings.master_volume);
+    if global_volume.volume != volume {
+        global_volume.volume = volume;
+    }
+}
+
 /// [`GlobalVolume`] doesn't apply to already-running audio entities, so this system will update them.
 fn apply_global_volume(
     global_volume: If<Res<GlobalVolume>>,
-    mut audio_query: Query<(&PlaybackSettings, &mut AudioSink)>,
+    settings: Res<Settings>,
+    mut audio_query: Query<(
+        &PlaybackSettings,
+        &mut AudioSink,
+        Has<Music>,
+        Has<SoundEffect>,
+    )>,
 ) {
-    for (playback, mut sink) in &mut audio_query {
-        sink.set_volume(global_volume.volume * playback.volume);
+    for (playback, mut sink, music, sound_effect) in &mut audio_query {
+        let channel = channel_volume(&settings, music, sound_effect);
+        sink.set_volume(global_volume.volume * playback.volume * channel);
     }
 }
+
+/// Bevy only applies the [`GlobalVolume`] when audio starts playing, so the
+/// channel volume is applied as soon as the sink exists.
+fn apply_channel_volume_on_play(
+    add: On<Add, AudioSink>,
+    global_volume: If<Res<GlobalVolume>>,
+    settings: Res<Settings>,
+    mut audio_query: Query<(
+        &PlaybackSettings,
+        &mut AudioSink,
+        Has<Music>,
+        Has<SoundEffect>,
+    )>,
+) {
+    let Ok((playback, mut sink, music, sound_effect)) = audio_query.get_mut(add.entity) else {
+        return;
+    };
+    let channel = channel_volume(&settings, music, sound_effect);
+    sink.set_volume(global_volume.volume * playback.volume * channel);
+}
@@ -1,4 +1,5 @@
 use bevy::prelude::*;
+use serde::Deserialize;
 
 //axis aligned bounding box
 #[derive(Component, Reflect, Default, Debug, Clone)]
@@ -54,4 +55,56 @@ impl AABB {
 
         return depth;
     }
+
+    /// Whether the segment from `start` to `end` passes through this box.
+    pub fn intersects_segment(&self, start: Vec2, end: Vec2) -> bool {
+        let min = self.center - self.half_size;
+        let max = self.center + self.half_size;
+        let direction = end - start;
+        let mut t_enter = 0.0_f32;
+        let mut t_exit = 1.0_f32;
+        for axis in 0..2 {
+            if direction[axis].abs() < f32::EPSILON {
+                // Parallel to this axis, so it must already be inside the slab.
+                if start[axis] < min[axis] || start[axis] > max[axis] {
+                    return false;
+                }
+                continue;
+            }
+            let t1 = (min[axis] - start[axis]) / direction[axis];
+            let t2 = (max[axis] - start[axis]) / direction[axis];
+            t_enter = t_enter.max(t1.min(t2));
+            t_exit = t_exit.min(t1.max(t2));
+            if t_enter > t_exit {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// Collision box of a moving entity, in sprite pixels before the entity's
+/// scale is applied. Entities without one use [`Hitbox::default`], which
+/// covers a whole 32x32 sprite.
+#[derive(Component, Reflect, Debug, Clone, Copy, Deserialize)]
+#[reflect(Component)]
+pub struct Hitbox {
+    pub half_size: Vec2,
+}
+
+impl Default for Hitbox {
+    fn default() -> Self {
+        Self {
+            half_size: Vec2::splat(16.0),
+        }
+    }
+}
+
+impl Hitbox {
+    pub fn aabb(&self, transform: &Transform) -> AABB {
+        AABB::new(
+            transform.translation.xy(),
+            self.half_size * transform.scale.xy(),
+        )
+    }
 }
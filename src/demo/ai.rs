@@ -0,0 +1,281 @@
+//! Enemy behaviour as a small finite state machine.
+//!
+//! Each enemy type fights according to an [`AiProfile`], picked by name from
+//! the [`AiProfiles`] asset (`assets/data/default.ai.ron`). The [`Brain`]
+//! picks a state from what the enemy can see and steers its
+//! [`MovementController`]. Attacks are not performed here: the brain triggers
+//! an [`AiAttack`] and the enemy's own module decides what that means.
+
+use std::collections::HashMap;
+
+use bevy::{
+    asset::{AssetLoader, LoadContext, io::Reader},
+    prelude::*,
+};
+use serde::Deserialize;
+
+use crate::{
+    AppSystems, PausableSystems,
+    asset_tracking::LoadResource,
+    demo::{
+        aabb::AABB, health::Health, movement::MovementController, platform::Platform,
+        player::Player,
+    },
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_asset::<AiProfiles>();
+    app.register_asset_loader(AiProfilesLoader);
+    app.load_resource::<AiAssets>();
+    app.add_systems(
+        Update,
+        (
+            tick_brains.in_set(AppSystems::TickTimers),
+            (think, act).chain().in_set(AppSystems::Update),
+        )
+            .in_set(PausableSystems),
+    );
+}
+
+/// How an enemy type behaves in a fight. Distances are in pixels along the x
+/// axis, durations in seconds.
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct AiProfile {
+    /// The player is noticed when closer than this and in line of sight.
+    pub sight_range: f32,
+    /// The enemy backs off when the player is closer than this.
+    pub min_range: f32,
+    /// The enemy attacks when the player is between `min_range` and this.
+    pub attack_range: f32,
+    pub attack_cooldown: f32,
+    /// Half-width of the patrol route around the spawn point. Zero stands still.
+    pub patrol_distance: f32,
+    /// Health fraction under which the enemy runs away. Zero never flees.
+    pub flee_below: f32,
+    pub stun_duration: f32,
+}
+
+/// The [`AiProfile`]s enemy types can use, by name.
+#[derive(Asset, Reflect, Debug, Deserialize)]
+#[serde(transparent)]
+pub struct AiProfiles(HashMap<String, AiProfile>);
+
+impl AiProfiles {
+    pub fn get(&self, name: &str) -> Option<&AiProfile> {
+        self.0.get(name)
+    }
+}
+
+#[derive(TypePath, Default)]
+struct AiProfilesLoader;
+
+impl AssetLoader for AiProfilesLoader {
+    type Asset = AiProfiles;
+    type Settings = ();
+    type Error = BevyError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(ron::de::from_bytes(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["ai.ron"]
+    }
+}
+
+#[derive(Resource, Asset, Clone, Reflect)]
+#[reflect(Resource)]
+pub struct AiAssets {
+    #[dependency]
+    pub profiles: Handle<AiProfiles>,
+}
+
+impl FromWorld for AiAssets {
+    fn from_world(world: &mut World) -> Self {
+        let assets = world.resource::<AssetServer>();
+        Self {
+            profiles: assets.load("data/default.ai.ron"),
+        }
+    }
+}
+
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AiState {
+    Idle,
+    Patrol,
+    Chase,
+    KeepDistance,
+    Attack,
+    Flee,
+    Stunned,
+}
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct Brain {
+    pub profile: AiProfile,
+    pub state: AiState,
+    /// Center of the patrol route.
+    home_x: f32,
+    patrol_right: bool,
+    /// Where the player was last seen, chased after losing sight of them.
+    last_seen_x: Option<f32>,
+    attack_cooldown: Timer,
+    stun_timer: Timer,
+}
+
+impl Brain {
+    pub fn new(profile: AiProfile, home_x: f32) -> Self {
+        Self {
+            profile,
+            state: AiState::Idle,
+            home_x,
+            patrol_right: true,
+            last_seen_x: None,
+            attack_cooldown: Timer::from_seconds(profile.attack_cooldown, TimerMode::Repeating),
+            stun_timer: Timer::from_seconds(profile.stun_duration, TimerMode::Once),
+        }
+    }
+
+    /// Interrupt whatever the enemy was doing for its stun duration.
+    pub fn stun(&mut self) {
+        self.state = AiState::Stunned;
+        self.stun_timer.reset();
+    }
+}
+
+/// Triggered when a [`Brain`] decides to attack.
+#[derive(EntityEvent, Debug)]
+pub struct AiAttack {
+    pub entity: Entity,
+    /// Position of the player being attacked.
+    pub target: Vec2,
+}
+
+fn tick_brains(time: Res<Time>, mut brain_query: Query<&mut Brain>) {
+    for mut brain in &mut brain_query {
+        if brain.state == AiState::Stunned {
+            brain.stun_timer.tick(time.delta());
+        } else if brain.state == AiState::Attack {
+            brain.attack_cooldown.tick(time.delta());
+        }
+    }
+}
+
+/// Whether no platform blocks the view between two points.
+pub fn line_of_sight(from: Vec2, to: Vec2, platform_query: &Query<&AABB, With<Platform>>) -> bool {
+    !platform_query
+        .iter()
+        .any(|platform| platform.intersects_segment(from, to))
+}
+
+fn think(
+    mut brain_query: Query<(&mut Brain, &Transform, Option<&Health>), Without<Player>>,
+    player_query: Query<&Transform, With<Player>>,
+    platform_query: Query<&AABB, With<Platform>>,
+) {
+    let player_position = player_query
+        .single()
+        .ok()
+        .map(|transform| transform.translation.xy());
+
+    for (mut brain, transform, health) in &mut brain_query {
+        if brain.state == AiState::Stunned && !brain.stun_timer.is_finished() {
+            continue;
+        }
+        let position = transform.translation.xy();
+        let sees_player = player_position.is_some_and(|player| {
+            (player.x - position.x).abs() <= brain.profile.sight_range
+                && line_of_sight(position, player, &platform_query)
+        });
+
+        let next_state = if let (true, Some(player)) = (sees_player, player_position) {
+            brain.last_seen_x = Some(player.x);
+            let distance = (player.x - position.x).abs();
+            let hurt = health.is_some_and(|health| health.fraction() < brain.profile.flee_below);
+            if hurt {
+                AiState::Flee
+            } else if distance > brain.profile.attack_range {
+                AiState::Chase
+            } else if distance < brain.profile.min_range {
+                AiState::KeepDistance
+            } else {
+                AiState::Attack
+            }
+        } else if brain
+            .last_seen_x
+            .is_some_and(|last_seen_x| (last_seen_x - position.x).abs() > 16.0)
+        {
+            AiState::Chase
+        } else {
+            brain.last_seen_x = None;
+            if brain.profile.patrol_distance > 0.0 {
+                AiState::Patrol
+            } else {
+                AiState::Idle
+            }
+        };
+
+        if next_state == AiState::Attack && brain.state != AiState::Attack {
+            // Wind up a full cooldown before the first attack.
+            brain.attack_cooldown.reset();
+        }
+        brain.state = next_state;
+    }
+}
+
+fn act(
+    mut commands: Commands,
+    mut brain_query: Query<(Entity, &mut Brain, &Transform, &mut MovementController)>,
+    player_query: Query<&Transform, With<Player>>,
+) {
+    let player_position = player_query
+        .single()
+        .ok()
+        .map(|transform| transform.translation.xy());
+
+    for (entity, mut brain, transform, mut movement) in &mut brain_query {
+        let x = transform.translation.x;
+        // Direction towards where the player was last seen, which is their
+        // current position while they are in sight.
+        let towards_target = brain
+            .last_seen_x
+            .or(player_position.map(|player| player.x))
+            .map_or(0.0, |target_x| (target_x - x).signum());
+
+        movement.horizontal = match brain.state {
+            AiState::Idle | AiState::Stunned | AiState::Attack => 0.0,
+            AiState::Patrol => {
+                let offset = x - brain.home_x;
+                if offset > brain.profile.patrol_distance {
+                    brain.patrol_right = false;
+                } else if offset < -brain.profile.patrol_distance {
+                    brain.patrol_right = true;
+                }
+                if brain.patrol_right { 0.5 } else { -0.5 }
+            }
+            AiState::Chase => towards_target,
+            AiState::KeepDistance | AiState::Flee => -towards_target,
+        };
+
+        if movement.horizontal != 0.0 {
+            movement.facing_right = movement.horizontal > 0.0;
+        }
+
+        if brain.state == AiState::Attack {
+            movement.facing_right = towards_target > 0.0;
+            if brain.attack_cooldown.just_finished()
+                && let Some(target) = player_position
+            {
+                commands.trigger(AiAttack { entity, target });
+            }
+        }
+    }
+}
@@ -12,6 +12,8 @@ use crate::{
     AppSystems, PausableSystems,
     audio::sound_effect,
     demo::{
+        ai::{AiState, Brain},
+        enemy::Enemy,
         movement::MovementController,
         player::{Player, PlayerAssets},
     },
@@ -44,13 +46,24 @@ fn update_animation_timer(time: Res<Time>, mut query: Query<&mut MovementAnimati
 
 /// Update the sprite direction and animation state (idling/walking).
 fn update_animation_movement(
-    mut player_query: Query<(&MovementController, &mut Sprite, &mut MovementAnimation)>,
+    mut player_query: Query<(
+        &MovementController,
+        &mut Sprite,
+        &mut MovementAnimation,
+        Option<&Brain>,
+        Option<&Enemy>,
+    )>,
 ) {
-    for (controller, mut sprite, mut animation) in &mut player_query {
+    for (controller, mut sprite, mut animation, brain, enemy) in &mut player_query {
         sprite.flip_x = !controller.facing_right;
 
-        let animation_state = if controller.gliding {
+        let animation_state = if brain.is_some_and(|brain| brain.state == AiState::Attack) {
+            MovementAnimationState::Attacking
+        } else if controller.gliding {
             MovementAnimationState::Gliding
+        } else if enemy.is_some_and(Enemy::is_flying) {
+            // Flying enemies flap their wings all the time.
+            MovementAnimationState::Walking
         } else if controller.horizontal == 0.0 || !controller.grounded {
             MovementAnimationState::Idling
         } else {
@@ -110,6 +123,7 @@ pub enum MovementAnimationState {
     Idling,
     Walking,
     Gliding,
+    Attacking,
 }
 
 impl MovementAnimation {
@@ -121,6 +135,8 @@ impl MovementAnimation {
     const WALKING_INTERVAL: Duration = Duration::from_millis(50);
     /// Number of gliding frames
     const GLIDING_FRAMES: usize = 1;
+    /// Number of attacking frames. Only enemy sprite sheets have them.
+    const ATTACKING_FRAMES: usize = 1;
 
     fn idling() -> Self {
         Self {
@@ -146,6 +162,14 @@ impl MovementAnimation {
         }
     }
 
+    fn attacking() -> Self {
+        Self {
+            timer: Timer::from_seconds(0.0, TimerMode::Repeating),
+            frame: 0,
+            state: MovementAnimationState::Attacking,
+        }
+    }
+
     pub fn new() -> Self {
         Self::idling()
     }
@@ -161,6 +185,7 @@ impl MovementAnimation {
                 MovementAnimationState::Idling => Self::IDLE_FRAMES,
                 MovementAnimationState::Walking => Self::WALKING_FRAMES,
                 MovementAnimationState::Gliding => Self::GLIDING_FRAMES,
+                MovementAnimationState::Attacking => Self::ATTACKING_FRAMES,
             };
     }
 
@@ -171,6 +196,7 @@ impl MovementAnimation {
                 MovementAnimationState::Idling => *self = Self::idling(),
                 MovementAnimationState::Walking => *self = Self::walking(),
                 MovementAnimationState::Gliding => *self = Self::gliding(),
+                MovementAnimationState::Attacking => *self = Self::attacking(),
             }
         }
     }
@@ -186,6 +212,7 @@ impl MovementAnimation {
             MovementAnimationState::Idling => 0,
             MovementAnimationState::Walking => 4 + self.frame,
             MovementAnimationState::Gliding => 8,
+            MovementAnimationState::Attacking => 9,
         }
     }
 }
@@ -1,11 +1,50 @@
-use bevy::{image::{ImageLoaderSettings, ImageSampler}, prelude::*};
+//! The boss fight at the end of the level.
+//!
+//! The boss sleeps until the goose is cursed enough and walks into its arena.
+//! After a short introduction it works through the attack pattern of its
+//! current [`BossPhase`], telegraphing every attack before it lands. Losing
+//! health moves it on to faster and nastier phases.
 
-use crate::{asset_tracking::LoadResource, demo::{enemy::{EnemyAssets, Garlic}, level::CurseLevel, movement::MovementController, player::Player}};
+use bevy::{
+    image::{ImageLoaderSettings, ImageSampler},
+    prelude::*,
+};
+
+use crate::{
+    AppSystems, PausableSystems,
+    asset_tracking::LoadResource,
+    audio::{Music, music},
+    demo::{
+        aabb::Hitbox,
+        camera::{CameraLock, CameraShake, CameraZoom},
+        curse::{CurseLevel, CurseModifiers},
+        cutscene::Cutscene,
+        enemy::{Enemies, EnemyAssets, Garlic},
+        explosion::Explosion,
+        health::{Died, Health},
+        movement::MovementController,
+        pickup::{Item, LootDrop, LootTable},
+        player::Player,
+        spawner::{Spawned, arena_wall, spawned_enemy},
+    },
+    localization::Localized,
+    screens::Screen,
+    settings::Settings,
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.load_resource::<BossAssets>();
-    app.add_systems(FixedUpdate, move_boss);
-    app.add_systems(Update, update_health_bar);
+    app.add_systems(
+        Update,
+        (
+            tick_boss.in_set(AppSystems::TickTimers),
+            (update_phase, run_boss, animate_boss, apply_charge_damage)
+                .chain()
+                .in_set(AppSystems::Update),
+        )
+            .in_set(PausableSystems),
+    );
+    app.add_observer(kill_boss);
 }
 
 #[derive(Resource, Asset, Clone, Reflect)]
@@ -13,8 +52,8 @@ pub(super) fn plugin(app: &mut App) {
 pub struct BossAssets {
     #[dependency]
     pub boss: Handle<Image>,
-    // #[dependency]
-    // pub music: Handle<AudioSource>,
+    #[dependency]
+    pub music: Handle<AudioSource>,
 }
 
 impl FromWorld for BossAssets {
@@ -25,120 +64,519 @@ impl FromWorld for BossAssets {
                 "images/boss.png",
                 |settings: &mut ImageLoaderSettings| settings.sampler = ImageSampler::nearest(),
             ),
+            music: assets.load("audio/music/Monkeys Spinning Monkeys.ogg"),
+        }
+    }
+}
+
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BossAttack {
+    /// Dash to where the player stood when the attack started.
+    Charge,
+    /// Jump and crash down, sending out shockwaves on both sides.
+    GroundSlam,
+    /// Drop garlic from the sky around the player.
+    GarlicRain,
+    /// Call in mushrooms to help.
+    Summon,
+}
+
+impl BossAttack {
+    /// Longest time the attack may take, before the phase tempo is applied.
+    fn duration_secs(self) -> f32 {
+        match self {
+            Self::Charge => 2.5,
+            Self::GroundSlam => 0.8,
+            Self::GarlicRain => 1.2,
+            Self::Summon => 0.6,
         }
     }
 }
 
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BossAction {
+    /// Waiting for the curse rules to wake it and the player to walk in.
+    Asleep,
+    Intro,
+    /// Catching its breath between attacks.
+    Recover,
+    /// Winding up so the player can see the attack coming.
+    Telegraph(BossAttack),
+    Attack(BossAttack),
+}
+
+/// One stage of the fight.
+#[derive(Debug)]
+pub struct BossPhase {
+    /// The phase starts once the boss's health fraction drops to this.
+    pub health_below: f32,
+    /// Attacks performed in order, then repeated.
+    pub pattern: &'static [BossAttack],
+    /// Multiplier on movement speed and on how quickly attacks follow each other.
+    pub tempo: f32,
+}
+
+pub const PHASES: [BossPhase; 3] = [
+    BossPhase {
+        health_below: 1.0,
+        pattern: &[BossAttack::Charge, BossAttack::GroundSlam],
+        tempo: 1.0,
+    },
+    BossPhase {
+        health_below: 0.66,
+        pattern: &[
+            BossAttack::Charge,
+            BossAttack::GarlicRain,
+            BossAttack::GroundSlam,
+        ],
+        tempo: 1.25,
+    },
+    BossPhase {
+        health_below: 0.33,
+        pattern: &[
+            BossAttack::Summon,
+            BossAttack::Charge,
+            BossAttack::GarlicRain,
+            BossAttack::GroundSlam,
+            BossAttack::Charge,
+        ],
+        tempo: 1.5,
+    },
+];
+
 #[derive(Component, Reflect, Debug)]
 #[reflect(Component)]
 pub struct Boss {
-    pub health: f32,
-    max_health: f32,
+    /// Index into [`PHASES`].
+    pub phase: usize,
+    pub action: BossAction,
     speed: f32,
-    target_x: f32,
-    move_cooldown: Timer,
-    attacked: bool,
+    pattern_step: usize,
+    /// Time left in the current action.
+    timer: Timer,
+    /// x coordinates of the arena walls.
+    arena: (f32, f32),
+    /// Height the boss hovers at between attacks.
+    hover_y: f32,
+    charge_x: f32,
+    /// Whether the current charge already hit the player.
+    charge_hit: bool,
 }
 
+impl Boss {
+    pub const NAME: &'static str = "The Mushroom King";
+    const SCALE: f32 = 5.0;
+    /// The boss hovers about this far above the floor of its arena.
+    const HOVER_HEIGHT: f32 = 200.0;
+    /// How far the camera zooms in on the boss during its introduction.
+    const INTRO_ZOOM: f32 = 1.25;
+
+    pub fn current_phase(&self) -> &'static BossPhase {
+        &PHASES[self.phase]
+    }
+
+    /// Whether the player has woken the boss up.
+    pub fn is_engaged(&self) -> bool {
+        self.action != BossAction::Asleep
+    }
+
+    fn floor_y(&self) -> f32 {
+        self.hover_y - Self::HOVER_HEIGHT
+    }
+
+    fn start(&mut self, action: BossAction, secs: f32) {
+        self.action = action;
+        self.timer = Timer::from_seconds(secs / self.current_phase().tempo, TimerMode::Once);
+    }
+
+    fn next_attack(&self) -> BossAttack {
+        let pattern = self.current_phase().pattern;
+        pattern[self.pattern_step % pattern.len()]
+    }
+}
+
+/// The boss, hovering at `location` in an arena walled off between
+/// `arena_left` and `arena_right` once the fight starts.
 pub fn boss(
     location: Vec2,
+    arena_left: f32,
+    arena_right: f32,
     boss_assests: &BossAssets,
-    meshes: &mut Assets<Mesh>,
-    materials: &mut Assets<ColorMaterial>,
 ) -> impl Bundle {
     (
+        Name::new("Boss"),
         Boss {
-            health: 1200.0,
-            max_health: 1200.0,
+            phase: 0,
+            action: BossAction::Asleep,
             speed: 500.0,
-            target_x: location.x,
-            move_cooldown: Timer::from_seconds(1.0, TimerMode::Repeating),
-            attacked: false,
+            pattern_step: 0,
+            timer: Timer::default(),
+            arena: (arena_left, arena_right),
+            hover_y: location.y,
+            charge_x: location.x,
+            charge_hit: false,
+        },
+        Health::new(1200.0),
+        LootTable(vec![
+            LootDrop::new(Item::Coin, 1.0, 20),
+            LootDrop::new(Item::Feather, 1.0, 3),
+        ]),
+        Hitbox {
+            half_size: Vec2::new(22.0, 26.0),
         },
-        Transform::from_translation(location.extend(3.0)).with_scale(Vec3::new(5.0, 5.0, 1.0)),
+        Transform::from_translation(location.extend(3.0))
+            .with_scale(Vec2::splat(Boss::SCALE).extend(1.0)),
         Sprite {
             image: boss_assests.boss.clone(),
             ..Default::default()
         },
-        children![
-            (
-                Mesh2d(meshes.add(Rectangle::new(80.0, 6.0))),
-                MeshMaterial2d(materials.add(Color::srgb(1.0, 0.0, 0.0))),
-                Transform::from_xyz(0.0, 50.0, 4.0),
-            ), 
-            (
-                Mesh2d(meshes.add(Rectangle::new(80.0, 6.0))),
-                MeshMaterial2d(materials.add(Color::srgb(0.0, 1.0, 0.0))),
-                Transform::from_xyz(0.0, 50.0, 5.0),
-                HealthBarBoss,
-            ), 
-        ]
     )
 }
 
-fn move_boss(
+/// Name shown above the boss during its introduction.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct BossTitle;
+
+/// Walls keeping the player in the boss arena until the boss is dead.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct BossWall;
+
+/// Music played instead of the level music during the fight.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct BossMusic;
+
+fn tick_boss(time: Res<Time>, mut boss_query: Query<&mut Boss>) {
+    for mut boss in &mut boss_query {
+        boss.timer.tick(time.delta());
+    }
+}
+
+/// Move on to the next phase when health drops below its threshold.
+fn update_phase(
+    mut boss_query: Query<(&mut Boss, &Health)>,
+    mut camera_shake: ResMut<CameraShake>,
+) {
+    for (mut boss, health) in &mut boss_query {
+        if !boss.is_engaged() || boss.action == BossAction::Intro {
+            continue;
+        }
+        let phase = PHASES
+            .iter()
+            .rposition(|phase| health.fraction() <= phase.health_below)
+            .unwrap_or(0);
+        if phase <= boss.phase {
+            continue;
+        }
+        boss.phase = phase;
+        boss.pattern_step = 0;
+        // Interrupt the current attack with an angry wind-up.
+        let attack = boss.next_attack();
+        boss.start(BossAction::Telegraph(attack), 1.5);
+        camera_shake.add_trauma(0.6);
+    }
+}
+
+fn run_boss(
     mut commands: Commands,
     time: Res<Time>,
-    mut boss_query: Query<(&mut Transform, &mut Boss), Without<Player>>,
-    player_query: Query<&Transform, (With<Player>, Without<Boss>)>,
+    curse_modifiers: Res<CurseModifiers>,
+    boss_assets: If<Res<BossAssets>>,
     enemy_assets: If<Res<EnemyAssets>>,
-    curse_level: If<Res<CurseLevel>>,
+    enemies: Enemies,
+    mut boss_query: Query<(Entity, &mut Boss, &mut Transform, Option<&Spawned>), Without<Player>>,
+    player_query: Query<&Transform, (With<Player>, Without<Boss>)>,
+    title_query: Query<Entity, With<BossTitle>>,
+    level_music_query: Query<&AudioSink, (With<Music>, Without<BossMusic>)>,
+    mut camera_lock: ResMut<CameraLock>,
+    mut camera_shake: ResMut<CameraShake>,
+    mut camera_zoom: ResMut<CameraZoom>,
+    mut next_cutscene: ResMut<NextState<Cutscene>>,
 ) {
-    if curse_level.value < 8 {
+    let Ok(player_transform) = player_query.single() else {
         return;
-    }
-    for player_transform in player_query {
-        for (mut boss_transform, mut boss) in &mut boss_query {
-            boss.move_cooldown.tick(time.delta());
-            if boss.move_cooldown.just_finished() {
-                boss.attacked = false;
-                boss.target_x = player_transform.translation.x;
-            }
-            let diff_x = boss.target_x - boss_transform.translation.x;
-            let sign = diff_x.signum();
-            if diff_x.abs() >= 30.0 {
-                boss_transform.translation.x += sign * boss.speed * time.delta_secs();
-            } else if !boss.attacked {
-                boss.attacked = true;
-                commands.spawn(
-               (
-                        Garlic,
-                        Transform::from_translation(boss_transform.translation)
-                            .with_scale(Vec3::new(1.5, 1.5, 1.0)),
-                        Sprite {
-                            image: enemy_assets.garlic.clone(),
-                            ..Default::default()
-                        },
-                        MovementController {
-                            speed: 0.0,
-                            horizontal: sign,
-                            velocity: Vec2::new(0.0, -250.0),
-                            gravity: 100.0,
-                            grounded: false,
-                            facing_right: diff_x > 0.0,
-                            ..Default::default()
+    };
+    let player_x = player_transform.translation.x;
+
+    for (entity, mut boss, mut transform, spawned) in &mut boss_query {
+        let (left, right) = boss.arena;
+        let tempo = boss.current_phase().tempo;
+        let x = transform.translation.x;
+
+        match boss.action {
+            BossAction::Asleep => {
+                if curse_modifiers.boss_awake
+                    && player_x > left + 128.0
+                    && player_x < right - 128.0
+                {
+                    boss.start(BossAction::Intro, 3.0);
+                    next_cutscene.set(Cutscene(true));
+                    // A zero-width region pins the camera on the boss.
+                    camera_lock.region = Some((x, x));
+                    camera_zoom.target = Boss::INTRO_ZOOM;
+                    camera_shake.add_trauma(0.4);
+                    commands.spawn((
+                        Name::new("Boss Title"),
+                        BossTitle,
+                        Text2d::new(Boss::NAME),
+                        Localized::new(Boss::NAME),
+                        TextFont {
+                            font_size: 60.0,
+                            ..default()
                         },
-                    )
-                );
-            } 
+                        Transform::from_xyz(x, boss.hover_y + 250.0, 12.0),
+                        DespawnOnExit(Screen::Gameplay),
+                    ));
+                }
+            }
+            BossAction::Intro => {
+                if !boss.timer.is_finished() {
+                    continue;
+                }
+                next_cutscene.set(Cutscene(false));
+                camera_lock.region = Some(boss.arena);
+                camera_zoom.target = 1.0;
+                for title in &title_query {
+                    commands.entity(title).despawn();
+                }
+                for wall_x in [left, right] {
+                    commands.spawn((
+                        arena_wall(wall_x, boss.floor_y()),
+                        BossWall,
+                        DespawnOnExit(Screen::Gameplay),
+                    ));
+                }
+                for sink in &level_music_query {
+                    sink.pause();
+                }
+                commands.spawn((
+                    Name::new("Boss Music"),
+                    music(boss_assets.music.clone()),
+                    BossMusic,
+                    DespawnOnExit(Screen::Gameplay),
+                ));
+                boss.start(BossAction::Recover, 1.0);
+            }
+            BossAction::Recover => {
+                if boss.timer.is_finished() {
+                    let attack = boss.next_attack();
+                    boss.start(BossAction::Telegraph(attack), 0.8);
+                }
+            }
+            BossAction::Telegraph(attack) => {
+                if !boss.timer.is_finished() {
+                    continue;
+                }
+                match attack {
+                    BossAttack::Charge => {
+                        boss.charge_x = player_x.clamp(left + 150.0, right - 150.0);
+                        boss.charge_hit = false;
+                    }
+                    BossAttack::GroundSlam => {}
+                    BossAttack::GarlicRain => {
+                        for i in 0..8 {
+                            let garlic_x = (player_x + (rand::random::<f32>() - 0.5) * 1200.0)
+                                .clamp(left + 48.0, right - 48.0);
+                            // Stagger the drops so they land one after another.
+                            let garlic_y = boss.hover_y + 500.0 + i as f32 * 120.0;
+                            commands.spawn(falling_garlic(
+                                Vec2::new(garlic_x, garlic_y),
+                                &enemy_assets,
+                            ));
+                        }
+                    }
+                    BossAttack::Summon => {
+                        // Don't let summoned mushrooms pile up over the fight.
+                        if spawned.map_or(0, Spawned::count) < 4 {
+                            for offset in [-300.0, 300.0] {
+                                let summon_x = (x + offset).clamp(left + 96.0, right - 96.0);
+                                let location = Vec2::new(summon_x, boss.floor_y());
+                                match spawned_enemy(entity, &enemies, "mushroom", location) {
+                                    Ok(mushroom) => {
+                                        commands.spawn(mushroom);
+                                    }
+                                    Err(error) => warn!("Boss can't summon: {error}"),
+                                }
+                            }
+                        }
+                    }
+                }
+                boss.start(BossAction::Attack(attack), attack.duration_secs());
+            }
+            BossAction::Attack(attack) => {
+                let mut finished = boss.timer.is_finished();
+                match attack {
+                    BossAttack::Charge => {
+                        let diff_x = boss.charge_x - x;
+                        let step = boss.speed * 2.5 * tempo * time.delta_secs();
+                        if diff_x.abs() <= step {
+                            transform.translation.x = boss.charge_x;
+                            finished = true;
+                        } else {
+                            transform.translation.x += diff_x.signum() * step;
+                        }
+                    }
+                    BossAttack::GroundSlam => {
+                        // Rise for the first part of the attack, then crash down.
+                        let progress = boss.timer.fraction();
+                        let slam_y = boss.floor_y() + 40.0;
+                        transform.translation.y = if progress < 0.6 {
+                            boss.hover_y + 200.0 * (progress / 0.6)
+                        } else {
+                            let fall = (progress - 0.6) / 0.4;
+                            (boss.hover_y + 200.0).lerp(slam_y, fall * fall)
+                        };
+                        if finished {
+                            transform.translation.y = slam_y;
+                            for offset in [-260.0, 260.0] {
+                                commands.spawn((
+                                    Explosion {
+                                        radius: 200.0,
+                                        damage: 35.0,
+                                        force: 1100.0,
+                                    },
+                                    Transform::from_xyz(x + offset, boss.floor_y() - 80.0, 11.0),
+                                ));
+                            }
+                        }
+                    }
+                    BossAttack::GarlicRain | BossAttack::Summon => {}
+                }
+                if finished {
+                    boss.pattern_step += 1;
+                    boss.start(BossAction::Recover, 1.2);
+                }
+            }
+        }
+
+        // Drift back to hovering height after slamming down.
+        if boss.action != BossAction::Attack(BossAttack::GroundSlam) {
+            let ease = (5.0 * time.delta_secs()).min(1.0);
+            transform.translation.y += (boss.hover_y - transform.translation.y) * ease;
         }
     }
 }
 
-#[derive(Component, Reflect, Debug)]
-#[reflect(Component)]
-struct HealthBarBoss;
-
+/// A garlic bulb falling straight down, exploding where it lands.
+fn falling_garlic(location: Vec2, enemy_assets: &EnemyAssets) -> impl Bundle {
+    (
+        Garlic,
+        Transform::from_translation(location.extend(4.0)).with_scale(Vec3::new(1.5, 1.5, 1.0)),
+        Sprite {
+            image: enemy_assets.garlic.clone(),
+            ..Default::default()
+        },
+        MovementController {
+            speed: 0.0,
+            velocity: Vec2::new(0.0, -100.0),
+            gravity: 40.0,
+            grounded: false,
+            ..Default::default()
+        },
+        DespawnOnExit(Screen::Gameplay),
+    )
+}
 
-fn update_health_bar(
-    boss_query: Query<&Boss>,
-    mut health_bar_query: Query<&mut Transform, With<HealthBarBoss>>,
+/// Flash, shake and squash the boss to telegraph what it is about to do.
+///
+/// With reduced motion the boss holds a steady tint and pose instead.
+fn animate_boss(
+    time: Res<Time>,
+    settings: Res<Settings>,
+    mut boss_query: Query<(&Boss, &mut Transform, &mut Sprite)>,
 ) {
-    for boss in boss_query {
-        for mut health_bar_transform in health_bar_query.iter_mut() {
-            health_bar_transform.scale.x = (boss.health / boss.max_health).max(0.0);
-            health_bar_transform.translation.x = (boss.health / boss.max_health) * 40.0 - 40.0;
+    let t = time.elapsed_secs();
+    let wobble = if settings.reduced_motion { 0.0 } else { 1.0 };
+    for (boss, mut transform, mut sprite) in &mut boss_query {
+        let mut color = Color::WHITE;
+        let mut rotation = 0.0;
+        let mut scale = Vec2::ONE;
+        match boss.action {
+            BossAction::Telegraph(attack) => {
+                if settings.reduced_motion || (t * 30.0).sin() > 0.0 {
+                    color = Color::srgb(1.0, 0.35, 0.35);
+                }
+                rotation = (t * 40.0).sin() * 0.06 * wobble;
+                match attack {
+                    // Crouch before jumping.
+                    BossAttack::GroundSlam => scale = Vec2::new(1.15, 0.8),
+                    // Lean back, away from where it will charge.
+                    BossAttack::Charge => rotation += 0.15,
+                    BossAttack::GarlicRain | BossAttack::Summon => scale = Vec2::new(0.9, 1.15),
+                }
+            }
+            BossAction::Intro => {
+                rotation = (t * 20.0).sin() * 0.03 * wobble;
+            }
+            BossAction::Attack(BossAttack::Charge) => {
+                rotation = -(boss.charge_x - transform.translation.x).signum() * 0.2;
+            }
+            _ => {}
+        }
+        sprite.color = color;
+        transform.rotation = Quat::from_rotation_z(rotation);
+        transform.scale = (scale * Boss::SCALE).extend(1.0);
+    }
+}
 
+fn apply_charge_damage(
+    settings: Res<Settings>,
+    curse_modifiers: Res<CurseModifiers>,
+    mut boss_query: Query<(&mut Boss, &Transform, &Hitbox), Without<Player>>,
+    mut player_query: Query<
+        (&Transform, &mut Health, &mut MovementController),
+        (With<Player>, Without<Boss>),
+    >,
+) {
+    let Ok((player_transform, mut player_health, mut player_movement)) = player_query.single_mut()
+    else {
+        return;
+    };
+    let player_aabb = Hitbox::default().aabb(player_transform);
+    for (mut boss, boss_transform, hitbox) in &mut boss_query {
+        if boss.action != BossAction::Attack(BossAttack::Charge) || boss.charge_hit {
+            continue;
+        }
+        if hitbox
+            .aabb(boss_transform)
+            .get_intersection_depth(&player_aabb)
+            == Vec2::ZERO
+        {
+            continue;
         }
-    } 
-}
\ No newline at end of file
+        boss.charge_hit = true;
+        player_health.current -=
+            30.0 * settings.difficulty.damage_taken() * curse_modifiers.enemy_damage;
+        let away = (player_transform.translation.x - boss_transform.translation.x).signum();
+        player_movement.knockback = away * 1200.0;
+        player_movement.velocity.y = 700.0;
+        player_movement.grounded = false;
+    }
+}
+
+fn kill_boss(
+    died: On<Died>,
+    mut commands: Commands,
+    boss_query: Query<(), With<Boss>>,
+    wall_query: Query<Entity, With<BossWall>>,
+    boss_music_query: Query<Entity, With<BossMusic>>,
+    level_music_query: Query<&AudioSink, (With<Music>, Without<BossMusic>)>,
+    mut camera_lock: ResMut<CameraLock>,
+    mut curse_level: If<ResMut<CurseLevel>>,
+) {
+    if !boss_query.contains(died.entity) {
+        return;
+    }
+    commands.entity(died.entity).despawn();
+    curse_level.add(100);
+
+    camera_lock.region = None;
+    for entity in wall_query.iter().chain(&boss_music_query) {
+        commands.entity(entity).despawn();
+    }
+    for sink in &level_music_query {
+        sink.play();
+    }
+}
@@ -0,0 +1,255 @@
+//! The camera rig following the goose.
+//!
+//! The camera eases towards its [`FollowCamera`] target instead of snapping to
+//! it. The target can move around a dead zone without moving the camera, the
+//! camera looks ahead in the direction the target faces, and it only follows
+//! upwards once the target glides high above the ground. The camera stays
+//! inside the level's [`CameraBounds`] and any [`CameraLock`] region, like an
+//! arena, and is shaken by trauma added to [`CameraShake`]. Scripted moments
+//! like the boss intro zoom in and out through [`CameraZoom`].
+
+use bevy::prelude::*;
+
+use crate::{
+    AppSystems, PausableSystems,
+    demo::movement::{self, MovementController},
+    screens::Screen,
+    settings::Settings,
+    viewport::{MainCamera, VirtualResolution},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<CameraShake>();
+    app.init_resource::<CameraLock>();
+    app.init_resource::<CameraBounds>();
+    app.init_resource::<CameraZoom>();
+    app.add_observer(add_rig_to_camera);
+    app.add_systems(OnEnter(Screen::Gameplay), snap_camera);
+    app.add_systems(OnExit(Screen::Gameplay), release_camera);
+    app.add_systems(
+        FixedUpdate,
+        update_camera_rig
+            .after(movement::handle_collisions)
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems),
+    );
+}
+
+/// The entity the camera follows.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct FollowCamera;
+
+/// How the camera follows its [`FollowCamera`] target.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct CameraRig {
+    /// How quickly the camera catches up, per second. Higher is snappier.
+    pub damping: f32,
+    /// Half the size of the box the target can move in without moving the camera.
+    pub dead_zone: Vec2,
+    /// How far ahead of the target the camera looks in its facing direction.
+    pub look_ahead: f32,
+    /// How far above the camera's resting height the target can go before the
+    /// camera follows it up.
+    pub follow_height: f32,
+    /// Height of the camera while the target is near the ground.
+    pub rest_y: f32,
+    /// Center of the dead zone, following the target once it leaves the zone.
+    anchor: Vec2,
+    /// Eased look-ahead, so turning around pans smoothly.
+    look_ahead_offset: f32,
+    /// Position of the camera without shake.
+    position: Vec2,
+    /// Eased zoom, following [`CameraZoom::target`].
+    zoom: f32,
+    /// Jump straight to the target on the next update instead of easing.
+    snap: bool,
+}
+
+impl CameraRig {
+    fn new(position: Vec2) -> Self {
+        Self {
+            damping: 6.0,
+            dead_zone: Vec2::new(40.0, 60.0),
+            look_ahead: 120.0,
+            follow_height: 150.0,
+            rest_y: position.y,
+            anchor: position,
+            look_ahead_offset: 0.0,
+            position,
+            zoom: 1.0,
+            snap: true,
+        }
+    }
+}
+
+/// Trauma-based camera shake. Trauma between 0 and 1 is added by impacts and
+/// decays over time; the shake offset grows with the square of trauma.
+#[derive(Resource, Reflect, Debug, Default)]
+#[reflect(Resource)]
+pub struct CameraShake {
+    trauma: f32,
+    offset: Vec2,
+}
+
+impl CameraShake {
+    const MAX_OFFSET: f32 = 24.0;
+    const DECAY_PER_SECOND: f32 = 1.5;
+
+    pub fn add_trauma(&mut self, amount: f32) {
+        self.trauma = (self.trauma + amount).min(1.0);
+    }
+}
+
+/// Keeps the camera between two x coordinates, e.g. the walls of an arena.
+#[derive(Resource, Reflect, Debug, Default)]
+#[reflect(Resource)]
+pub struct CameraLock {
+    pub region: Option<(f32, f32)>,
+}
+
+/// The part of the current level the camera may show, set when the level spawns.
+#[derive(Resource, Reflect, Debug, Default)]
+#[reflect(Resource)]
+pub struct CameraBounds(pub Option<Rect>);
+
+/// How far the camera is zoomed in. The camera eases towards the target, so
+/// cutscenes can set it once and let the rig animate the zoom.
+#[derive(Resource, Reflect, Debug)]
+#[reflect(Resource)]
+pub struct CameraZoom {
+    /// Magnification of the view, where 2 shows half as much of the level.
+    pub target: f32,
+}
+
+impl CameraZoom {
+    /// Seconds for the zoom to get most of the way to its target.
+    const EASE_SECONDS: f32 = 0.6;
+}
+
+impl Default for CameraZoom {
+    fn default() -> Self {
+        Self { target: 1.0 }
+    }
+}
+
+fn add_rig_to_camera(
+    add: On<Add, MainCamera>,
+    mut commands: Commands,
+    transform_query: Query<&Transform>,
+) {
+    let position = transform_query
+        .get(add.entity)
+        .map_or(Vec2::ZERO, |transform| transform.translation.truncate());
+    commands.entity(add.entity).insert(CameraRig::new(position));
+}
+
+fn snap_camera(mut rig_query: Query<&mut CameraRig>) {
+    for mut rig in &mut rig_query {
+        rig.snap = true;
+    }
+}
+
+fn release_camera(
+    mut camera_lock: ResMut<CameraLock>,
+    mut camera_bounds: ResMut<CameraBounds>,
+    mut camera_shake: ResMut<CameraShake>,
+    mut camera_zoom: ResMut<CameraZoom>,
+) {
+    camera_lock.region = None;
+    camera_bounds.0 = None;
+    camera_shake.trauma = 0.0;
+    camera_zoom.target = 1.0;
+}
+
+/// Center of a view `half_size` wide on one axis, kept between `min` and `max`.
+/// A range narrower than the view is centered on instead.
+fn clamp_view(center: f32, half_size: f32, min: f32, max: f32) -> f32 {
+    if max - min <= half_size * 2.0 {
+        (min + max) / 2.0
+    } else {
+        center.clamp(min + half_size, max - half_size)
+    }
+}
+
+fn update_camera_rig(
+    time: Res<Time>,
+    settings: Res<Settings>,
+    mut camera_shake: ResMut<CameraShake>,
+    camera_lock: Res<CameraLock>,
+    camera_bounds: Res<CameraBounds>,
+    camera_zoom: Res<CameraZoom>,
+    resolution: Res<VirtualResolution>,
+    mut camera_query: Query<(&mut Transform, &mut Projection, &mut CameraRig)>,
+    target_query: Query<
+        (&Transform, Option<&MovementController>),
+        (With<FollowCamera>, Without<CameraRig>),
+    >,
+) {
+    let Ok((mut camera_transform, mut projection, mut rig)) = camera_query.single_mut() else {
+        return;
+    };
+    let dt = time.delta_secs();
+
+    if rig.snap {
+        rig.zoom = camera_zoom.target;
+    } else {
+        let ease = 1.0 - (-dt / CameraZoom::EASE_SECONDS * 3.0).exp();
+        rig.zoom += (camera_zoom.target - rig.zoom) * ease;
+    }
+    if let Projection::Orthographic(orthographic) = &mut *projection {
+        orthographic.scale = 1.0 / rig.zoom;
+    }
+
+    if let Ok((target_transform, controller)) = target_query.single() {
+        let target = target_transform.translation.truncate();
+        let facing = match controller {
+            Some(controller) if !controller.facing_right => -1.0,
+            _ => 1.0,
+        };
+        let look_ahead = facing * rig.look_ahead;
+        let ease = 1.0 - (-rig.damping * dt).exp();
+        if rig.snap {
+            rig.anchor = target;
+            rig.look_ahead_offset = look_ahead;
+        }
+        // The anchor only moves by as much as the target has left the dead zone.
+        let distance = target - rig.anchor;
+        let outside = (distance.abs() - rig.dead_zone).max(Vec2::ZERO);
+        rig.anchor += outside * distance.signum();
+        rig.look_ahead_offset += (look_ahead - rig.look_ahead_offset) * ease;
+
+        let mut goal = Vec2::new(
+            rig.anchor.x + rig.look_ahead_offset,
+            (rig.anchor.y - rig.follow_height).max(rig.rest_y),
+        );
+        let half_size = resolution.0.as_vec2() / 2.0 / rig.zoom;
+        if let Some(bounds) = camera_bounds.0 {
+            goal.x = clamp_view(goal.x, half_size.x, bounds.min.x, bounds.max.x);
+            goal.y = clamp_view(goal.y, half_size.y, bounds.min.y, bounds.max.y);
+        }
+        if let Some((left, right)) = camera_lock.region {
+            goal.x = clamp_view(goal.x, half_size.x, left, right);
+        }
+
+        if rig.snap {
+            rig.snap = false;
+            rig.position = goal;
+        } else {
+            rig.position = rig.position.lerp(goal, ease);
+        }
+    }
+
+    let shake = camera_shake.trauma * camera_shake.trauma;
+    camera_shake.offset = Vec2::new(
+        rand::random::<f32>() * 2.0 - 1.0,
+        rand::random::<f32>() * 2.0 - 1.0,
+    ) * shake
+        * CameraShake::MAX_OFFSET
+        * settings.shake_strength();
+    camera_shake.trauma = (camera_shake.trauma - CameraShake::DECAY_PER_SECOND * dt).max(0.0);
+    let position = rig.position + camera_shake.offset;
+    camera_transform.translation.x = position.x;
+    camera_transform.translation.y = position.y;
+}
@@ -0,0 +1,87 @@
+//! Checkpoints the goose comes back to when it dies.
+//!
+//! Walking past a checkpoint's flag makes it the [`ActiveCheckpoint`]. Dying
+//! after that respawns the goose there with full health instead of ending the
+//! game.
+
+use bevy::prelude::*;
+
+use crate::{
+    AppSystems, PausableSystems,
+    audio::sound_effect,
+    demo::player::{Player, PlayerAssets},
+    screens::Screen,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<ActiveCheckpoint>();
+    app.add_systems(OnEnter(Screen::Gameplay), reset_checkpoint);
+    app.add_systems(
+        Update,
+        reach_checkpoints
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems),
+    );
+}
+
+/// A flag standing on the ground at its spawn point.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct Checkpoint {
+    /// Where the goose respawns.
+    pub spawn_point: Vec2,
+}
+
+impl Checkpoint {
+    const FLAG_SIZE: Vec2 = Vec2::new(8.0, 96.0);
+    const COLOR: Color = Color::srgb(0.55, 0.5, 0.45);
+    const REACHED_COLOR: Color = Color::srgb(0.95, 0.8, 0.25);
+    /// How close the goose has to get to reach it.
+    const REACH: Vec2 = Vec2::new(48.0, 160.0);
+}
+
+/// Spawn point of the last checkpoint reached this run.
+#[derive(Resource, Reflect, Debug, Default)]
+#[reflect(Resource)]
+pub struct ActiveCheckpoint(pub Option<Vec2>);
+
+pub fn checkpoint(spawn_point: Vec2) -> impl Bundle {
+    (
+        Name::new("Checkpoint"),
+        Checkpoint { spawn_point },
+        Sprite::from_color(Checkpoint::COLOR, Checkpoint::FLAG_SIZE),
+        Transform::from_translation(
+            (spawn_point + Vec2::Y * Checkpoint::FLAG_SIZE.y / 2.0).extend(2.0),
+        ),
+    )
+}
+
+fn reset_checkpoint(mut active_checkpoint: ResMut<ActiveCheckpoint>) {
+    active_checkpoint.0 = None;
+}
+
+fn reach_checkpoints(
+    mut commands: Commands,
+    player_assets: If<Res<PlayerAssets>>,
+    mut active_checkpoint: ResMut<ActiveCheckpoint>,
+    player_query: Query<&Transform, With<Player>>,
+    mut checkpoint_query: Query<(&Checkpoint, &mut Sprite)>,
+) {
+    let Ok(player_transform) = player_query.single() else {
+        return;
+    };
+    let player_position = player_transform.translation.truncate();
+    for (checkpoint, mut sprite) in &mut checkpoint_query {
+        let reached = ((player_position - checkpoint.spawn_point).abs())
+            .cmple(Checkpoint::REACH)
+            .all();
+        if !reached || active_checkpoint.0 == Some(checkpoint.spawn_point) {
+            continue;
+        }
+        active_checkpoint.0 = Some(checkpoint.spawn_point);
+        sprite.color = Checkpoint::REACHED_COLOR;
+        if let Some(honk) = player_assets.honk.first() {
+            commands.spawn(sound_effect(honk.clone()));
+        }
+    }
+}
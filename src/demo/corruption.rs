@@ -0,0 +1,318 @@
+//! How the curse shows on screen.
+//!
+//! A meter on the HUD tracks the [`CurseLevel`] through the tiers of the
+//! [`CurseRules`], and reaching a new tier is announced in the middle of the
+//! screen. The `Corruption` set by the rules tints the barn and hay and
+//! drives the camera's [`ScreenEffect`], easing in so tier changes don't pop.
+//! With reduced motion the screen effect and throbbing are left out.
+
+use bevy::prelude::*;
+
+use crate::{
+    AppSystems, PausableSystems,
+    demo::{
+        camera::CameraShake,
+        curse::{CurseAssets, CurseLevel, CurseLevelChanged, CurseModifiers, CurseRules},
+        pickup::{Item, Pickup},
+    },
+    localization::Localization,
+    screen_effect::ScreenEffect,
+    screens::Screen,
+    settings::Settings,
+    theme::{
+        palette::{CURSE_METER_FILL, CURSE_PIP, CURSE_PIP_REACHED},
+        style::ThemedText,
+        widget::{self, Meter},
+    },
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<Corruption>();
+    app.add_systems(OnExit(Screen::Gameplay), reset_corruption);
+    app.add_systems(
+        Update,
+        (
+            tick_announcements
+                .in_set(AppSystems::TickTimers)
+                .in_set(PausableSystems),
+            (
+                ease_corruption.in_set(PausableSystems),
+                (update_screen_effect, corrupt_sprites, update_curse_meter),
+            )
+                .chain()
+                .in_set(AppSystems::Update),
+        )
+            .run_if(in_state(Screen::Gameplay)),
+    );
+    app.add_observer(announce_tier);
+    app.add_observer(corrupt_hay);
+}
+
+/// How corrupted the world currently looks, between 0 and 1.
+///
+/// Follows [`CurseModifiers::corruption`] at a limited speed.
+#[derive(Resource, Reflect, Debug, Default)]
+#[reflect(Resource)]
+pub struct Corruption(pub f32);
+
+impl Corruption {
+    /// Change per second while catching up with the curse rules.
+    const EASE_SPEED: f32 = 0.4;
+}
+
+/// A sprite that takes on a sickly tint as the world gets corrupted.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct Corruptible;
+
+impl Corruptible {
+    /// Tint of fully corrupted sprites.
+    const TINT: Color = Color::srgb(0.62, 0.45, 0.72);
+}
+
+fn reset_corruption(
+    mut corruption: ResMut<Corruption>,
+    mut screen_effect_query: Query<&mut ScreenEffect>,
+) {
+    corruption.0 = 0.0;
+    for mut screen_effect in &mut screen_effect_query {
+        *screen_effect = ScreenEffect::default();
+    }
+}
+
+fn ease_corruption(
+    time: Res<Time>,
+    modifiers: Res<CurseModifiers>,
+    mut corruption: ResMut<Corruption>,
+) {
+    let max_step = Corruption::EASE_SPEED * time.delta_secs();
+    let step = (modifiers.corruption - corruption.0).clamp(-max_step, max_step);
+    corruption.0 += step;
+}
+
+fn update_screen_effect(
+    corruption: Res<Corruption>,
+    settings: Res<Settings>,
+    mut screen_effect_query: Query<&mut ScreenEffect>,
+) {
+    let amount = if settings.reduced_motion {
+        0.0
+    } else {
+        corruption.0
+    };
+    for mut screen_effect in &mut screen_effect_query {
+        *screen_effect = ScreenEffect {
+            tint: LinearRgba::new(0.75, 0.55, 1.0, amount * 0.6),
+            vignette: amount * 0.8,
+            vignette_tint: amount,
+            desaturation: amount * 0.5,
+            contrast: 1.0 + amount * 0.2,
+        };
+    }
+}
+
+/// Hay lying around the level rots along with the world.
+fn corrupt_hay(add: On<Add, Pickup>, mut commands: Commands, pickup_query: Query<&Pickup>) {
+    if pickup_query
+        .get(add.entity)
+        .is_ok_and(|pickup| pickup.item == Item::Hay)
+    {
+        commands.entity(add.entity).insert(Corruptible);
+    }
+}
+
+fn corrupt_sprites(
+    time: Res<Time>,
+    corruption: Res<Corruption>,
+    settings: Res<Settings>,
+    mut sprite_query: Query<&mut Sprite, With<Corruptible>>,
+) {
+    // Badly corrupted sprites throb slowly.
+    let throb = if settings.reduced_motion {
+        0.0
+    } else {
+        (time.elapsed_secs() * 3.0).sin() * 0.1 * corruption.0 * corruption.0
+    };
+    let color = Color::WHITE
+        .mix(&Corruptible::TINT, corruption.0)
+        .darker(throb.max(0.0))
+        .lighter((-throb).max(0.0));
+    for mut sprite in &mut sprite_query {
+        sprite.color = color;
+    }
+}
+
+/// Label showing the name of the current tier.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct CurseMeterLabel;
+
+/// Progress from the current tier to the next.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct CurseMeterBar;
+
+/// One marker per tier, lit once the tier is reached.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct CursePip(usize);
+
+/// Meter following the curse through its tiers, with a pip for every tier.
+pub fn curse_meter(tier_count: usize) -> impl Bundle {
+    // The first tier is where every run starts, so it doesn't get a pip.
+    let pips = (1..tier_count).map(|index| {
+        (
+            Name::new("Curse Pip"),
+            CursePip(index),
+            Node {
+                width: px(10),
+                height: px(10),
+                ..default()
+            },
+            BackgroundColor(CURSE_PIP),
+        )
+    });
+    (
+        Name::new("Curse Meter"),
+        Node {
+            flex_direction: FlexDirection::Column,
+            row_gap: px(4),
+            ..default()
+        },
+        children![
+            (CurseMeterLabel, widget::small_label("")),
+            (
+                CurseMeterBar,
+                widget::meter("Curse Meter Bar", px(160), px(12), CURSE_METER_FILL),
+            ),
+            (
+                Name::new("Curse Pips"),
+                Node {
+                    column_gap: px(4),
+                    ..default()
+                },
+                Children::spawn(SpawnIter(pips)),
+            ),
+        ],
+    )
+}
+
+fn update_curse_meter(
+    curse_level: Res<CurseLevel>,
+    curse_assets: If<Res<CurseAssets>>,
+    curse_rules: Res<Assets<CurseRules>>,
+    localization: Localization,
+    mut label_query: Query<&mut Text, With<CurseMeterLabel>>,
+    mut bar_query: Query<&mut Meter, With<CurseMeterBar>>,
+    mut pip_query: Query<(&CursePip, &mut BackgroundColor)>,
+) {
+    let Some(rules) = curse_rules.get(&curse_assets.rules) else {
+        return;
+    };
+    let Some(index) = rules.tier_index(curse_level.value) else {
+        return;
+    };
+    let tier = &rules.tiers[index];
+    let progress = match rules.tiers.get(index + 1) {
+        Some(next) => {
+            (curse_level.value - tier.threshold) as f32 / (next.threshold - tier.threshold) as f32
+        }
+        None => 1.0,
+    };
+    for mut text in &mut label_query {
+        let label =
+            localization.format("Curse: {tier}", &[("tier", &localization.get(&tier.name))]);
+        if text.0 != label {
+            text.0 = label;
+        }
+    }
+    for mut meter in &mut bar_query {
+        if meter.fraction != progress {
+            meter.fraction = progress;
+        }
+    }
+    for (pip, mut background) in &mut pip_query {
+        let color = if pip.0 <= index {
+            CURSE_PIP_REACHED
+        } else {
+            CURSE_PIP
+        };
+        background.set_if_neq(BackgroundColor(color));
+    }
+}
+
+/// Text announcing a newly reached tier, fading out over its lifetime.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct TierAnnouncement(Timer);
+
+fn announce_tier(
+    changed: On<CurseLevelChanged>,
+    mut commands: Commands,
+    curse_assets: If<Res<CurseAssets>>,
+    curse_rules: Res<Assets<CurseRules>>,
+    announcement_query: Query<Entity, With<TierAnnouncement>>,
+    mut camera_shake: ResMut<CameraShake>,
+    localization: Localization,
+) {
+    // Nothing is announced at the start of a run.
+    let Some(previous) = changed.previous else {
+        return;
+    };
+    let Some(rules) = curse_rules.get(&curse_assets.rules) else {
+        return;
+    };
+    let Some(tier) = rules
+        .tiers
+        .iter()
+        .rfind(|tier| tier.threshold > previous && tier.threshold <= changed.value)
+    else {
+        return;
+    };
+    for entity in &announcement_query {
+        commands.entity(entity).despawn();
+    }
+    camera_shake.add_trauma(0.25);
+    commands.spawn((
+        Name::new("Tier Announcement"),
+        TierAnnouncement(Timer::from_seconds(2.5, TimerMode::Once)),
+        Node {
+            position_type: PositionType::Absolute,
+            width: percent(100),
+            top: percent(30),
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        Pickable::IGNORE,
+        DespawnOnExit(Screen::Gameplay),
+        children![(
+            Text::new(localization.format(
+                "The curse deepens: {tier}",
+                &[("tier", &localization.get(&tier.name))],
+            )),
+            ThemedText::Header,
+        )],
+    ));
+}
+
+fn tick_announcements(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut announcement_query: Query<(Entity, &mut TierAnnouncement, &Children)>,
+    mut text_color_query: Query<&mut TextColor>,
+) {
+    for (entity, mut announcement, children) in &mut announcement_query {
+        announcement.0.tick(time.delta());
+        if announcement.0.is_finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        // Hold for the first half, then fade.
+        let alpha = (announcement.0.fraction_remaining() * 2.0).min(1.0);
+        for child in children {
+            if let Ok(mut text_color) = text_color_query.get_mut(*child) {
+                text_color.0.set_alpha(alpha);
+            }
+        }
+    }
+}
@@ -0,0 +1,355 @@
+//! The curse that grows as the goose eats and fights.
+//!
+//! What each [`CurseLevel`] does is authored in a [`CurseRules`] asset
+//! (`assets/data/default.curse.ron`) rather than in code. Whenever the level
+//! changes, a [`CurseLevelChanged`] event is triggered and the rules are
+//! evaluated again: every tier whose threshold has been reached applies its
+//! effects, with later tiers overriding earlier ones.
+
+use std::collections::HashMap;
+
+use bevy::{
+    asset::{AssetLoader, LoadContext, io::Reader},
+    image::{ImageLoaderSettings, ImageSampler},
+    prelude::*,
+    sprite_render::TilemapChunk,
+};
+use serde::Deserialize;
+
+use crate::{
+    AppSystems,
+    asset_tracking::LoadResource,
+    audio::music,
+    demo::{
+        enemy::Enemy,
+        gun::Gun,
+        level::LevelMusic,
+        movement::MovementController,
+        parallax::ParallaxLayer,
+        player::Player,
+        tilemap::{TileChunk, tileset_settings},
+    },
+    localization::Localized,
+    screens::Screen,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_asset::<CurseRules>();
+    app.register_asset_loader(CurseRulesLoader);
+    app.load_resource::<CurseAssets>();
+    app.init_resource::<CurseLevel>();
+    app.init_resource::<CurseModifiers>();
+    app.add_systems(OnEnter(Screen::Gameplay), reset_curse);
+    app.add_systems(
+        Update,
+        (detect_curse_change, apply_enemy_modifiers)
+            .chain()
+            .in_set(AppSystems::Update),
+    );
+    app.add_observer(apply_curse_rules);
+}
+
+#[derive(Resource, Reflect, Debug, Default)]
+#[reflect(Resource)]
+pub struct CurseLevel {
+    pub value: u32,
+    /// The value the rules were last evaluated for.
+    applied: Option<u32>,
+}
+
+impl CurseLevel {
+    pub fn add(&mut self, amount: i32) {
+        self.value = self.value.saturating_add_signed(amount);
+    }
+}
+
+/// Triggered when [`CurseLevel::value`] changes, and once at the start of a run.
+#[derive(Event, Debug)]
+pub struct CurseLevelChanged {
+    /// `None` at the start of a run.
+    pub previous: Option<u32>,
+    pub value: u32,
+}
+
+/// Gameplay values currently set by the curse rules.
+#[derive(Resource, Reflect, Debug)]
+#[reflect(Resource)]
+pub struct CurseModifiers {
+    /// Gun cooldown in seconds, or `None` for the gun's own.
+    pub gun_cooldown: Option<f32>,
+    pub enemy_speed: f32,
+    pub enemy_damage: f32,
+    /// Whether the boss is allowed to wake up.
+    pub boss_awake: bool,
+    /// How corrupted the world should look, between 0 and 1.
+    pub corruption: f32,
+}
+
+impl Default for CurseModifiers {
+    fn default() -> Self {
+        Self {
+            gun_cooldown: None,
+            enemy_speed: 1.0,
+            enemy_damage: 1.0,
+            boss_awake: false,
+            corruption: 0.0,
+        }
+    }
+}
+
+/// Things whose texture the curse can swap.
+#[derive(Reflect, Debug, Clone, PartialEq, Eq, Hash, Deserialize)]
+pub enum CurseTarget {
+    /// The tileset of the tile layers with this name.
+    Tiles(String),
+    /// The [`ParallaxLayer`]s with this name.
+    Parallax(String),
+}
+
+#[derive(Reflect, Debug, Clone, Deserialize)]
+pub enum CurseEffect {
+    /// Swap the texture of every sprite of a kind.
+    Retexture {
+        target: CurseTarget,
+        image: String,
+    },
+    /// Override the gun's cooldown, in seconds.
+    GunCooldown(f32),
+    /// Multiply the speed and damage of all enemies.
+    EnemyStats {
+        speed: f32,
+        damage: f32,
+    },
+    /// Replace the level music.
+    Music(String),
+    /// Show a message above the goose when the tier is reached.
+    Text {
+        text: String,
+        size: f32,
+    },
+    WakeBoss,
+    /// How corrupted the world looks, between 0 and 1.
+    Corruption(f32),
+}
+
+#[derive(Reflect, Debug, Clone, Deserialize)]
+pub struct CurseTier {
+    pub threshold: u32,
+    /// Shown on the curse meter and announced when the tier is reached.
+    #[serde(default)]
+    pub name: String,
+    pub effects: Vec<CurseEffect>,
+}
+
+#[derive(Asset, Reflect, Debug, Deserialize)]
+pub struct CurseRules {
+    /// Tiers in order of increasing threshold.
+    pub tiers: Vec<CurseTier>,
+    /// Textures used by the tiers, loaded along with the rules.
+    #[serde(skip)]
+    images: HashMap<String, Handle<Image>>,
+    #[serde(skip)]
+    music: HashMap<String, Handle<AudioSource>>,
+}
+
+impl CurseRules {
+    /// Index of the highest tier reached at a curse level.
+    pub fn tier_index(&self, value: u32) -> Option<usize> {
+        self.tiers.iter().rposition(|tier| tier.threshold <= value)
+    }
+}
+
+#[derive(TypePath, Default)]
+struct CurseRulesLoader;
+
+impl AssetLoader for CurseRulesLoader {
+    type Asset = CurseRules;
+    type Settings = ();
+    type Error = BevyError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let mut rules: CurseRules = ron::de::from_bytes(&bytes)?;
+        rules.tiers.sort_by_key(|tier| tier.threshold);
+
+        for effect in rules.tiers.iter().flat_map(|tier| &tier.effects) {
+            match effect {
+                CurseEffect::Retexture { target, image } => {
+                    let loader = load_context.loader();
+                    let handle = if let CurseTarget::Tiles(_) = target {
+                        loader.with_settings(tileset_settings).load(image.clone())
+                    } else {
+                        loader
+                            .with_settings(|settings: &mut ImageLoaderSettings| {
+                                settings.sampler = ImageSampler::nearest();
+                            })
+                            .load(image.clone())
+                    };
+                    rules.images.insert(image.clone(), handle);
+                }
+                CurseEffect::Music(path) => {
+                    let handle = load_context.load(path.clone());
+                    rules.music.insert(path.clone(), handle);
+                }
+                _ => {}
+            }
+        }
+        Ok(rules)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["curse.ron"]
+    }
+}
+
+#[derive(Resource, Asset, Clone, Reflect)]
+#[reflect(Resource)]
+pub struct CurseAssets {
+    #[dependency]
+    pub rules: Handle<CurseRules>,
+}
+
+impl FromWorld for CurseAssets {
+    fn from_world(world: &mut World) -> Self {
+        let assets = world.resource::<AssetServer>();
+        Self {
+            rules: assets.load("data/default.curse.ron"),
+        }
+    }
+}
+
+fn reset_curse(mut curse_level: ResMut<CurseLevel>) {
+    *curse_level = CurseLevel::default();
+}
+
+fn detect_curse_change(mut commands: Commands, mut curse_level: ResMut<CurseLevel>) {
+    if curse_level.applied == Some(curse_level.value) {
+        return;
+    }
+    commands.trigger(CurseLevelChanged {
+        previous: curse_level.applied,
+        value: curse_level.value,
+    });
+    curse_level.applied = Some(curse_level.value);
+}
+
+fn apply_curse_rules(
+    changed: On<CurseLevelChanged>,
+    mut commands: Commands,
+    curse_assets: If<Res<CurseAssets>>,
+    curse_rules: Res<Assets<CurseRules>>,
+    mut modifiers: ResMut<CurseModifiers>,
+    chunk_query: Query<(Entity, &TileChunk, &TilemapChunk)>,
+    mut layer_query: Query<(&ParallaxLayer, &mut Sprite)>,
+    mut gun_query: Query<&mut Gun>,
+    player_query: Query<&Transform, With<Player>>,
+    level_music_query: Query<(Entity, &AudioPlayer, Option<&AudioSink>), With<LevelMusic>>,
+) {
+    let Some(rules) = curse_rules.get(&curse_assets.rules) else {
+        return;
+    };
+    let mut state = CurseModifiers::default();
+    let mut textures = HashMap::new();
+    let mut level_music = None;
+    for tier in rules
+        .tiers
+        .iter()
+        .filter(|tier| tier.threshold <= changed.value)
+    {
+        let just_reached = changed
+            .previous
+            .is_none_or(|previous| tier.threshold > previous);
+        for effect in &tier.effects {
+            match effect {
+                CurseEffect::Retexture { target, image } => {
+                    textures.insert(target.clone(), rules.images[image].clone());
+                }
+                CurseEffect::GunCooldown(secs) => state.gun_cooldown = Some(*secs),
+                CurseEffect::EnemyStats { speed, damage } => {
+                    state.enemy_speed = *speed;
+                    state.enemy_damage = *damage;
+                }
+                CurseEffect::Music(path) => level_music = Some(rules.music[path].clone()),
+                CurseEffect::Text { text, size } => {
+                    if !just_reached {
+                        continue;
+                    }
+                    for player_transform in &player_query {
+                        commands.spawn((
+                            Text2d::new(text.clone()),
+                            Localized::new(text.clone()),
+                            Transform::from_xyz(player_transform.translation.x, 0.0, 10.0),
+                            TextFont {
+                                font_size: *size,
+                                ..default()
+                            },
+                            DespawnOnExit(Screen::Gameplay),
+                        ));
+                    }
+                }
+                CurseEffect::WakeBoss => state.boss_awake = true,
+                CurseEffect::Corruption(amount) => state.corruption = *amount,
+            }
+        }
+    }
+
+    for (entity, chunk, tilemap_chunk) in &chunk_query {
+        if let Some(image) = textures.get(&CurseTarget::Tiles(chunk.layer.clone()))
+            && tilemap_chunk.tileset != *image
+        {
+            // Chunks only build their material when inserted.
+            commands.entity(entity).insert(TilemapChunk {
+                tileset: image.clone(),
+                ..tilemap_chunk.clone()
+            });
+        }
+    }
+    for (layer, mut sprite) in &mut layer_query {
+        if let Some(image) = textures.get(&CurseTarget::Parallax(layer.name.clone())) {
+            sprite.image = image.clone();
+        }
+    }
+    let cooldown = state.gun_cooldown.unwrap_or(Gun::DEFAULT_COOLDOWN);
+    for mut gun in &mut gun_query {
+        if gun.shooting_cooldown.duration().as_secs_f32() != cooldown {
+            gun.shooting_cooldown = Timer::from_seconds(cooldown, TimerMode::Repeating);
+        }
+    }
+    if let Some(handle) = level_music {
+        for (entity, player, sink) in &level_music_query {
+            if player.0 == handle {
+                continue;
+            }
+            // Keep the new track paused if the old one was, e.g. during the boss fight.
+            let paused = sink.is_some_and(|sink| sink.is_paused());
+            commands.entity(entity).despawn();
+            let mut new_music = commands.spawn((
+                Name::new("Gameplay Music"),
+                music(handle.clone()),
+                LevelMusic,
+                DespawnOnExit(Screen::Gameplay),
+            ));
+            if paused {
+                new_music.insert(PlaybackSettings::LOOP.paused());
+            }
+        }
+    }
+    *modifiers = state;
+}
+
+fn apply_enemy_modifiers(
+    modifiers: Res<CurseModifiers>,
+    mut enemy_query: Query<&mut MovementController, With<Enemy>>,
+) {
+    for mut movement in &mut enemy_query {
+        if movement.speed_multiplier != modifiers.enemy_speed {
+            movement.speed_multiplier = modifiers.enemy_speed;
+        }
+    }
+}
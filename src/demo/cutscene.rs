@@ -0,0 +1,32 @@
+//! Short scripted moments, like a boss introduction, during which the player
+//! cannot be controlled.
+
+use bevy::prelude::*;
+
+use crate::{
+    demo::{gun::Gun, movement::MovementController, player::Player},
+    screens::Screen,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_state::<Cutscene>();
+    app.add_systems(OnEnter(Cutscene(true)), freeze_player);
+    app.add_systems(OnExit(Screen::Gameplay), end_cutscene);
+}
+
+/// Whether a cutscene is playing. Player input is ignored while it is.
+#[derive(States, Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub struct Cutscene(pub bool);
+
+/// Drop whatever the player was holding down when the cutscene started.
+fn freeze_player(mut player_query: Query<(&mut MovementController, &mut Gun), With<Player>>) {
+    for (mut controller, mut gun) in &mut player_query {
+        controller.horizontal = 0.0;
+        controller.gliding = false;
+        gun.shooting = false;
+    }
+}
+
+fn end_cutscene(mut next_cutscene: ResMut<NextState<Cutscene>>) {
+    next_cutscene.set(Cutscene(false));
+}
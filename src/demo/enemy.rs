@@ -1,84 +1,285 @@
+//! Regular enemies. Every enemy type is an [`EnemyDefinition`] in the
+//! [`EnemyRegistry`] (`assets/data/default.enemies.ron`) and is spawned with
+//! the same [`enemy`] bundle, usually by its id through [`Enemies`].
+
+use std::collections::HashMap;
+
 use bevy::{
+    asset::{AssetLoader, LoadContext, io::Reader},
+    ecs::system::SystemParam,
     image::{ImageLoaderSettings, ImageSampler},
     prelude::*,
 };
+use serde::{Deserialize, Deserializer};
 
 use crate::{
+    AppSystems, PausableSystems,
     asset_tracking::LoadResource,
     demo::{
+        aabb::{AABB, Hitbox},
+        ai::{AiAssets, AiAttack, AiProfile, AiProfiles, Brain},
         animation::MovementAnimation,
+        curse::{CurseLevel, CurseModifiers},
+        health::{Died, Health},
         movement::MovementController,
+        pickup::{LootDrop, LootTable},
+        platform::Platform,
         player::Player,
     },
+    settings::Settings,
 };
 
 pub(super) fn plugin(app: &mut App) {
+    app.init_asset::<EnemyRegistry>();
+    app.register_asset_loader(EnemyRegistryLoader);
     app.load_resource::<EnemyAssets>();
-    app.add_systems(Update, (update_enemies, explode));
+    app.add_systems(
+        Update,
+        (
+            tick_contact_damage.in_set(AppSystems::TickTimers),
+            (fly, apply_contact_damage, move_projectiles).in_set(AppSystems::Update),
+        )
+            .in_set(PausableSystems),
+    );
+    app.add_observer(attack);
+    app.add_observer(kill_enemy);
 }
 
 #[derive(Component, Reflect, Debug)]
 #[reflect(Component)]
 pub struct Enemy {
-    pub health: f32,
-    pub max_health: f32,
-    pub garlic_cooldown: Timer,
-    pub can_attack: bool,
+    pub attack: EnemyAttack,
+    /// Damage dealt to the player on touch. Zero is harmless.
+    pub contact_damage: f32,
+    contact_cooldown: Timer,
+}
+
+impl Enemy {
+    /// Flying enemies ignore gravity and hold their altitude.
+    pub fn is_flying(&self) -> bool {
+        matches!(self.attack, EnemyAttack::Swoop { .. })
+    }
+}
+
+/// What an enemy does when its [`Brain`] decides to attack.
+#[derive(Reflect, Debug, Clone, Deserialize)]
+pub enum EnemyAttack {
+    /// Lob a [`Garlic`] that explodes where it lands.
+    Garlic,
+    /// Leap at the player, relying on contact damage to hurt them.
+    Lunge { force: f32 },
+    /// Dive down to the player's height, then climb back to `cruise_y`.
+    Swoop {
+        #[serde(skip)]
+        cruise_y: f32,
+        #[serde(skip)]
+        dive_y: f32,
+        /// How long a dive lasts, written in seconds.
+        #[serde(deserialize_with = "once_timer")]
+        dive: Timer,
+    },
+    /// Spit an [`EnemyProjectile`] straight at the player.
+    Shoot { speed: f32, damage: f32 },
+}
+
+/// Read a [`Timer`] written as its duration in seconds.
+fn once_timer<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Timer, D::Error> {
+    f32::deserialize(deserializer).map(|secs| Timer::from_seconds(secs, TimerMode::Once))
 }
 
 #[derive(Resource, Asset, Clone, Reflect)]
 #[reflect(Resource)]
 pub struct EnemyAssets {
     #[dependency]
-    pub mushroom: Handle<Image>,
+    registry: Handle<EnemyRegistry>,
     #[dependency]
     pub garlic: Handle<Image>,
+    #[dependency]
+    pub seed: Handle<Image>,
+    /// Shared by every enemy's sprite sheet.
+    layout: Handle<TextureAtlasLayout>,
 }
 
 impl FromWorld for EnemyAssets {
     fn from_world(world: &mut World) -> Self {
+        let layout =
+            world
+                .resource_mut::<Assets<TextureAtlasLayout>>()
+                .add(TextureAtlasLayout::from_grid(
+                    UVec2::splat(32),
+                    4,
+                    3,
+                    Some(UVec2::splat(1)),
+                    None,
+                ));
         let assets = world.resource::<AssetServer>();
+        let load = |path: &'static str| {
+            assets.load_with_settings(path, |settings: &mut ImageLoaderSettings| {
+                settings.sampler = ImageSampler::nearest()
+            })
+        };
         Self {
-            mushroom: assets.load_with_settings(
-                "images/mushroom.png",
-                |settings: &mut ImageLoaderSettings| settings.sampler = ImageSampler::nearest(),
-            ),
-            garlic: assets
-                .load_with_settings("images/garlic.png", |settings: &mut ImageLoaderSettings| {
-                    settings.sampler = ImageSampler::nearest()
-                }),
+            registry: assets.load("data/default.enemies.ron"),
+            garlic: load("images/garlic.png"),
+            seed: load("images/seed.png"),
+            layout,
+        }
+    }
+}
+
+/// Everything needed to spawn one type of enemy. Sprite sheets share the
+/// goose's layout, see [`MovementAnimation`].
+#[derive(Debug, Clone, Deserialize)]
+pub struct EnemyDefinition {
+    pub name: String,
+    /// Path of the sprite sheet.
+    pub sprite: String,
+    #[serde(skip)]
+    image: Handle<Image>,
+    pub health: f32,
+    pub speed: f32,
+    /// Sprite scale, randomly increased by up to `scale_variance`.
+    pub scale: f32,
+    #[serde(default)]
+    pub scale_variance: f32,
+    #[serde(default)]
+    pub hitbox: Hitbox,
+    /// Name of its profile in the [`AiProfiles`].
+    pub ai: String,
+    pub attack: EnemyAttack,
+    #[serde(default)]
+    pub contact_damage: f32,
+    #[serde(default)]
+    pub loot: Vec<LootDrop>,
+}
+
+impl EnemyDefinition {
+    pub fn is_flying(&self) -> bool {
+        matches!(self.attack, EnemyAttack::Swoop { .. })
+    }
+}
+
+/// All known enemy types by id.
+#[derive(Asset, TypePath, Debug, Deserialize)]
+#[serde(transparent)]
+pub struct EnemyRegistry(HashMap<String, EnemyDefinition>);
+
+impl EnemyRegistry {
+    pub fn get(&self, id: &str) -> Option<&EnemyDefinition> {
+        self.0.get(id)
+    }
+}
+
+#[derive(TypePath, Default)]
+struct EnemyRegistryLoader;
+
+impl AssetLoader for EnemyRegistryLoader {
+    type Asset = EnemyRegistry;
+    type Settings = ();
+    type Error = BevyError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let mut registry: EnemyRegistry = ron::de::from_bytes(&bytes)?;
+        for definition in registry.0.values_mut() {
+            definition.image = load_context
+                .loader()
+                .with_settings(|settings: &mut ImageLoaderSettings| {
+                    settings.sampler = ImageSampler::nearest();
+                })
+                .load(definition.sprite.clone());
         }
+        Ok(registry)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["enemies.ron"]
     }
 }
 
-pub fn mushroom(
-    health: f32,
+/// Everything needed to spawn enemies by their id in the [`EnemyRegistry`].
+#[derive(SystemParam)]
+pub struct Enemies<'w> {
+    registries: Res<'w, Assets<EnemyRegistry>>,
+    enemy_assets: If<Res<'w, EnemyAssets>>,
+    ai_assets: If<Res<'w, AiAssets>>,
+    ai_profiles: Res<'w, Assets<AiProfiles>>,
+}
+
+impl Enemies<'_> {
+    pub fn definition(&self, id: &str) -> Option<&EnemyDefinition> {
+        self.registries
+            .get(&self.enemy_assets.registry)
+            .and_then(|registry| registry.get(id))
+    }
+
+    /// An enemy of type `id` standing at `location`.
+    pub fn enemy(&self, id: &str, location: Vec3) -> Result<impl Bundle + use<>> {
+        let definition = self
+            .definition(id)
+            .ok_or_else(|| format!("unknown enemy type `{id}`"))?;
+        let ai_profile = self
+            .ai_profiles
+            .get(&self.ai_assets.profiles)
+            .and_then(|profiles| profiles.get(&definition.ai))
+            .ok_or_else(|| format!("unknown AI profile `{}`", definition.ai))?;
+        Ok(enemy(definition, *ai_profile, location, &self.enemy_assets))
+    }
+}
+
+/// An enemy of the given type standing at `location`.
+pub fn enemy(
+    definition: &EnemyDefinition,
+    ai_profile: AiProfile,
     location: Vec3,
-    size_modifier: f32,
     enemy_assets: &EnemyAssets,
-    texture_atlas_layouts: &mut Assets<TextureAtlasLayout>,
-) -> impl Bundle {
-    let layout = TextureAtlasLayout::from_grid(UVec2::splat(32), 4, 3, Some(UVec2::splat(1)), None);
-    let texture_atlas_layout = texture_atlas_layouts.add(layout);
+) -> impl Bundle + use<> {
     let enemy_animation = MovementAnimation::new();
+    let scale = definition.scale + rand::random::<f32>() * definition.scale_variance;
+
+    let mut attack = definition.attack.clone();
+    let mut gravity = MovementController::default().gravity;
+    if let EnemyAttack::Swoop {
+        cruise_y,
+        dive_y,
+        dive,
+    } = &mut attack
+    {
+        *cruise_y = location.y;
+        *dive_y = location.y;
+        // Hold the cruising altitude until the first real swoop.
+        let duration = dive.duration();
+        dive.tick(duration);
+        gravity = 0.0;
+    }
 
     (
+        Name::new(definition.name.clone()),
         Enemy {
-            health: health,
-            max_health: health,
-            garlic_cooldown: Timer::from_seconds(1.2, TimerMode::Repeating),
-            can_attack: true,
+            attack,
+            contact_damage: definition.contact_damage,
+            contact_cooldown: Timer::from_seconds(0.8, TimerMode::Once),
         },
-        Transform::from_translation(location).with_scale(Vec2::splat(size_modifier).extend(1.0)),
+        Health::new(definition.health),
+        LootTable(definition.loot.clone()),
+        definition.hitbox,
+        Transform::from_translation(location).with_scale(Vec2::splat(scale).extend(1.0)),
         MovementController {
-            speed: 300.0,
+            speed: definition.speed,
+            gravity,
             ..default()
         },
-        AI,
+        Brain::new(ai_profile, location.x),
         Sprite::from_atlas_image(
-            enemy_assets.mushroom.clone(),
+            definition.image.clone(),
             TextureAtlas {
-                layout: texture_atlas_layout,
+                layout: enemy_assets.layout.clone(),
                 index: enemy_animation.get_atlas_index(),
             },
         ),
@@ -86,93 +287,186 @@ pub fn mushroom(
     )
 }
 
-#[derive(Component, Reflect, Debug)]
-#[reflect(Component)]
-struct AI;
-
-fn update_enemies(
+fn attack(
+    attack: On<AiAttack>,
     mut commands: Commands,
-    time: Res<Time>,
-    mut ai_query: Query<
-        (&Transform, &mut MovementController, &mut Enemy),
-        (With<AI>, Without<Player>),
-    >,
-    player_query: Query<&Transform, (With<Player>, Without<AI>)>,
+    mut enemy_query: Query<(&Transform, &Brain, &mut Enemy, &mut MovementController)>,
     enemy_assets: If<Res<EnemyAssets>>,
 ) {
-    for player_transform in player_query {
-        for (ai_transform, mut ai_movement, mut enemy) in &mut ai_query {
-            let range_min = 300.0;
-            let range_max = 500.0;
-            let aggro_range = 1500.0;
-            let diff_x = player_transform.translation.x - ai_transform.translation.x;
-            if diff_x.abs() > aggro_range {
-                continue;
-            }
-            ai_movement.facing_right = diff_x > 0.0;
-            ai_movement.horizontal = 0.0;
-            // Player on the left
-            let sign = diff_x.signum();
-            // player too far = move closer
-            if diff_x.abs() > range_max {
-                ai_movement.horizontal = sign * 1.0;
-            } else if diff_x.abs() < range_min {
-                ai_movement.horizontal = sign * -1.0;
-            } else {
-                enemy.garlic_cooldown.tick(time.delta());
-                if enemy.garlic_cooldown.just_finished() {
-                    commands.spawn((
-                        Garlic,
-                        Transform::from_translation(ai_transform.translation)
-                            .with_scale(Vec3::new(1.5, 1.5, 1.0)),
-                        Sprite {
-                            image: enemy_assets.garlic.clone(),
-                            ..Default::default()
-                        },
-                        MovementController {
-                            speed: diff_x.abs() / range_max * 1111.0,
-                            horizontal: sign,
-                            velocity: Vec2::new(0.0, 1500.0),
-                            gravity: 100.0,
-                            grounded: false,
-                            facing_right: diff_x > 0.0,
-                            ..Default::default()
-                        },
-                    ));
-                }
-            }
+    let Ok((enemy_transform, brain, mut enemy, mut movement)) = enemy_query.get_mut(attack.entity)
+    else {
+        return;
+    };
+    let offset = attack.target - enemy_transform.translation.xy();
+    match &mut enemy.attack {
+        EnemyAttack::Garlic => {
+            commands.spawn((
+                Garlic,
+                Transform::from_translation(enemy_transform.translation)
+                    .with_scale(Vec3::new(1.5, 1.5, 1.0)),
+                Sprite {
+                    image: enemy_assets.garlic.clone(),
+                    ..Default::default()
+                },
+                MovementController {
+                    // Throw further the further away the player is.
+                    speed: offset.x.abs() / brain.profile.attack_range * 1111.0,
+                    horizontal: offset.x.signum(),
+                    velocity: Vec2::new(0.0, 1500.0),
+                    gravity: 100.0,
+                    grounded: false,
+                    facing_right: offset.x > 0.0,
+                    ..Default::default()
+                },
+            ));
+        }
+        EnemyAttack::Lunge { force } => {
+            movement.knockback = offset.x.signum() * *force;
+            movement.velocity.y = *force * 0.3;
+            movement.grounded = false;
+        }
+        EnemyAttack::Swoop { dive_y, dive, .. } => {
+            *dive_y = attack.target.y;
+            dive.reset();
+            movement.knockback = offset.x * 1.5;
+        }
+        EnemyAttack::Shoot { speed, damage } => {
+            let direction = offset.normalize_or(Vec2::X);
+            commands.spawn((
+                EnemyProjectile {
+                    velocity: direction * *speed,
+                    damage: *damage,
+                    lifetime: Timer::from_seconds(3.0, TimerMode::Once),
+                },
+                Transform::from_translation(enemy_transform.translation.xy().extend(4.0))
+                    .with_scale(Vec3::new(2.0, 2.0, 1.0))
+                    .with_rotation(Quat::from_rotation_z(direction.to_angle())),
+                Sprite {
+                    image: enemy_assets.seed.clone(),
+                    ..Default::default()
+                },
+            ));
         }
     }
 }
 
+/// Keep flying enemies at their cruising altitude, or dive while swooping.
+fn fly(time: Res<Time>, mut enemy_query: Query<(&mut Enemy, &Transform, &mut MovementController)>) {
+    for (mut enemy, transform, mut movement) in &mut enemy_query {
+        let EnemyAttack::Swoop {
+            cruise_y,
+            dive_y,
+            dive,
+        } = &mut enemy.attack
+        else {
+            continue;
+        };
+        dive.tick(time.delta());
+        let target_y = if dive.is_finished() {
+            *cruise_y
+        } else {
+            *dive_y
+        };
+        movement.velocity.y = ((target_y - transform.translation.y) * 4.0).clamp(-900.0, 900.0);
+    }
+}
+
+fn tick_contact_damage(time: Res<Time>, mut enemy_query: Query<&mut Enemy>) {
+    for mut enemy in &mut enemy_query {
+        enemy.contact_cooldown.tick(time.delta());
+    }
+}
+
+fn apply_contact_damage(
+    curse_modifiers: Res<CurseModifiers>,
+    settings: Res<Settings>,
+    mut enemy_query: Query<(&Transform, &Hitbox, &mut Enemy), Without<Player>>,
+    mut player_query: Query<
+        (&Transform, &mut Health, &mut MovementController),
+        (With<Player>, Without<Enemy>),
+    >,
+) {
+    let Ok((player_transform, mut player_health, mut player_movement)) = player_query.single_mut()
+    else {
+        return;
+    };
+    let player_aabb = Hitbox::default().aabb(player_transform);
+    for (enemy_transform, hitbox, mut enemy) in &mut enemy_query {
+        if enemy.contact_damage <= 0.0 || !enemy.contact_cooldown.is_finished() {
+            continue;
+        }
+        let enemy_aabb = hitbox.aabb(enemy_transform);
+        if enemy_aabb.get_intersection_depth(&player_aabb) == Vec2::ZERO {
+            continue;
+        }
+        enemy.contact_cooldown.reset();
+        player_health.current -= enemy.contact_damage
+            * curse_modifiers.enemy_damage
+            * settings.difficulty.damage_taken();
+        let away = (player_transform.translation.x - enemy_transform.translation.x).signum();
+        player_movement.knockback = away * 900.0;
+        player_movement.velocity.y = 600.0;
+        player_movement.grounded = false;
+    }
+}
+
 #[derive(Component, Reflect, Debug)]
 #[reflect(Component)]
 pub struct Garlic;
 
+/// A straight-flying shot fired at the player.
 #[derive(Component, Reflect, Debug)]
 #[reflect(Component)]
-pub struct Explosion {
-    pub radius: f32,
+pub struct EnemyProjectile {
+    pub velocity: Vec2,
+    pub damage: f32,
+    pub lifetime: Timer,
 }
 
-fn explode(
+fn move_projectiles(
     mut commands: Commands,
-    player_query: Query<(&Transform, &mut Player)>,
-    explosion_query: Query<(&Transform, &Explosion, Entity)>,
-    mut app_exit: MessageWriter<AppExit>
+    time: Res<Time>,
+    curse_modifiers: Res<CurseModifiers>,
+    settings: Res<Settings>,
+    mut projectile_query: Query<(Entity, &mut Transform, &mut EnemyProjectile)>,
+    mut player_query: Query<(&Transform, &mut Health), (With<Player>, Without<EnemyProjectile>)>,
+    platform_query: Query<&AABB, With<Platform>>,
 ) {
-    for (player_transform, mut player) in player_query {
-        for (explosion_transform, explosion, explosion_entity) in explosion_query {
-            let distance = player_transform
-                .translation
-                .distance(explosion_transform.translation);
-            if distance < explosion.radius {
-                player.health -= 40.0;
-                if player.health <= 0.0 { 
-                    app_exit.write(AppExit::Success);
-                }
+    'projectile: for (entity, mut transform, mut projectile) in &mut projectile_query {
+        projectile.lifetime.tick(time.delta());
+        transform.translation += (projectile.velocity * time.delta_secs()).extend(0.0);
+        let projectile_aabb = AABB::new(transform.translation.xy(), Vec2::splat(6.0));
+        if projectile.lifetime.is_finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        for platform_aabb in &platform_query {
+            if projectile_aabb.get_intersection_depth(platform_aabb) != Vec2::ZERO {
+                commands.entity(entity).despawn();
+                continue 'projectile;
+            }
+        }
+        for (player_transform, mut player_health) in &mut player_query {
+            let player_aabb = Hitbox::default().aabb(player_transform);
+            if projectile_aabb.get_intersection_depth(&player_aabb) != Vec2::ZERO {
+                player_health.current -= projectile.damage
+                    * curse_modifiers.enemy_damage
+                    * settings.difficulty.damage_taken();
+                commands.entity(entity).despawn();
+                continue 'projectile;
             }
-            commands.get_entity(explosion_entity).unwrap().despawn();
         }
     }
 }
+
+fn kill_enemy(
+    died: On<Died>,
+    mut commands: Commands,
+    enemy_query: Query<(), With<Enemy>>,
+    mut curse_level: If<ResMut<CurseLevel>>,
+) {
+    if enemy_query.contains(died.entity) {
+        commands.entity(died.entity).despawn();
+        curse_level.add(1);
+    }
+}
@@ -0,0 +1,229 @@
+//! Explosions hurt and push away everything with [`Health`] in their radius,
+//! including the enemies that caused them.
+
+use bevy::{
+    audio::Volume,
+    image::{ImageLoaderSettings, ImageSampler},
+    prelude::*,
+};
+
+use crate::{
+    AppSystems, PausableSystems,
+    asset_tracking::LoadResource,
+    audio::SoundEffect,
+    demo::{ai::Brain, camera::CameraShake, health::Health, movement::MovementController},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.load_resource::<ExplosionAssets>();
+    app.add_systems(
+        Update,
+        (
+            (tick_explosion_animation, tick_debris).in_set(AppSystems::TickTimers),
+            explode.in_set(AppSystems::Update),
+        )
+            .in_set(PausableSystems),
+    );
+}
+
+#[derive(Resource, Asset, Clone, Reflect)]
+#[reflect(Resource)]
+pub struct ExplosionAssets {
+    #[dependency]
+    sprite_sheet: Handle<Image>,
+    /// Shared by every explosion, so blasts don't add a layout each.
+    layout: Handle<TextureAtlasLayout>,
+    #[dependency]
+    sound: Handle<AudioSource>,
+}
+
+impl FromWorld for ExplosionAssets {
+    fn from_world(world: &mut World) -> Self {
+        let layout = world
+            .resource_mut::<Assets<TextureAtlasLayout>>()
+            .add(TextureAtlasLayout::from_grid(
+                UVec2::splat(32),
+                ExplosionAnimation::FRAMES as u32,
+                1,
+                None,
+                None,
+            ));
+        let assets = world.resource::<AssetServer>();
+        Self {
+            sprite_sheet: assets.load_with_settings(
+                "images/explosion.png",
+                |settings: &mut ImageLoaderSettings| settings.sampler = ImageSampler::nearest(),
+            ),
+            layout,
+            // The gunshot played back slowly makes for a convincing boom.
+            sound: assets.load("audio/sound_effects/gunshot.ogg"),
+        }
+    }
+}
+
+/// A one-shot blast. Spawn it with a [`Transform`] and it will deal its damage
+/// on the next update, then stay around only as a visual effect.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct Explosion {
+    pub radius: f32,
+    /// Damage dealt at the center, falling off linearly to zero at the edge.
+    pub damage: f32,
+    /// Knockback speed at the center, with the same falloff as damage.
+    pub force: f32,
+}
+
+impl Default for Explosion {
+    fn default() -> Self {
+        Self {
+            radius: 128.0,
+            damage: 60.0,
+            force: 900.0,
+        }
+    }
+}
+
+/// Marks an explosion that already dealt its damage.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct Detonated;
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct ExplosionAnimation {
+    timer: Timer,
+    frame: usize,
+}
+
+impl ExplosionAnimation {
+    const FRAMES: usize = 6;
+}
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct Debris {
+    velocity: Vec2,
+    lifetime: Timer,
+}
+
+fn explode(
+    mut commands: Commands,
+    explosion_assets: If<Res<ExplosionAssets>>,
+    mut camera_shake: ResMut<CameraShake>,
+    explosion_query: Query<(Entity, &Transform, &Explosion), Without<Detonated>>,
+    mut target_query: Query<
+        (
+            &Transform,
+            &mut Health,
+            Option<&mut MovementController>,
+            Option<&mut Brain>,
+        ),
+        Without<Explosion>,
+    >,
+) {
+    for (explosion_entity, explosion_transform, explosion) in &explosion_query {
+        let center = explosion_transform.translation.xy();
+        for (target_transform, mut health, movement, brain) in &mut target_query {
+            let offset = target_transform.translation.xy() - center;
+            let distance = offset.length();
+            if distance >= explosion.radius {
+                continue;
+            }
+            let falloff = 1.0 - distance / explosion.radius;
+            health.current -= explosion.damage * falloff;
+
+            if let Some(mut movement) = movement {
+                // Always throw targets a little upwards so they leave the ground.
+                let direction = (offset.normalize_or_zero() + Vec2::Y).normalize();
+                let push = direction * explosion.force * falloff;
+                movement.knockback += push.x;
+                movement.velocity.y = movement.velocity.y.max(0.0) + push.y;
+                movement.grounded = false;
+            }
+            if let Some(mut brain) = brain {
+                brain.stun();
+            }
+        }
+
+        camera_shake.add_trauma(0.5);
+
+        // Sized so the visible blast roughly matches the damage radius.
+        let scale = explosion.radius / 16.0;
+        commands.entity(explosion_entity).insert((
+            Detonated,
+            Sprite::from_atlas_image(
+                explosion_assets.sprite_sheet.clone(),
+                TextureAtlas {
+                    layout: explosion_assets.layout.clone(),
+                    index: 0,
+                },
+            ),
+            Transform::from_translation(center.extend(11.0))
+                .with_scale(Vec2::splat(scale).extend(1.0)),
+            ExplosionAnimation {
+                timer: Timer::from_seconds(0.07, TimerMode::Repeating),
+                frame: 0,
+            },
+        ));
+        commands.spawn((
+            AudioPlayer(explosion_assets.sound.clone()),
+            PlaybackSettings::DESPAWN
+                .with_speed(0.5)
+                .with_volume(Volume::Linear(1.5)),
+            SoundEffect,
+        ));
+        for _ in 0..12 {
+            let angle = rand::random::<f32>() * std::f32::consts::TAU;
+            let speed = 200.0 + rand::random::<f32>() * 400.0;
+            commands.spawn((
+                Debris {
+                    velocity: Vec2::from_angle(angle) * speed,
+                    lifetime: Timer::from_seconds(
+                        0.4 + rand::random::<f32>() * 0.4,
+                        TimerMode::Once,
+                    ),
+                },
+                Sprite::from_color(Color::srgb(1.0, 0.6, 0.2), Vec2::splat(6.0)),
+                Transform::from_translation(center.extend(11.0)),
+            ));
+        }
+    }
+}
+
+fn tick_explosion_animation(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut animation_query: Query<(Entity, &mut ExplosionAnimation, &mut Sprite)>,
+) {
+    for (entity, mut animation, mut sprite) in &mut animation_query {
+        animation.timer.tick(time.delta());
+        if !animation.timer.just_finished() {
+            continue;
+        }
+        animation.frame += 1;
+        if animation.frame >= ExplosionAnimation::FRAMES {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        if let Some(atlas) = sprite.texture_atlas.as_mut() {
+            atlas.index = animation.frame;
+        }
+    }
+}
+
+fn tick_debris(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut debris_query: Query<(Entity, &mut Debris, &mut Transform, &mut Sprite)>,
+) {
+    for (entity, mut debris, mut transform, mut sprite) in &mut debris_query {
+        debris.lifetime.tick(time.delta());
+        if debris.lifetime.is_finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        debris.velocity.y -= 1500.0 * time.delta_secs();
+        transform.translation += (debris.velocity * time.delta_secs()).extend(0.0);
+        sprite.color.set_alpha(debris.lifetime.fraction_remaining());
+    }
+}
@@ -1,58 +1,407 @@
-use bevy::prelude::*;
+//! What eating does to the goose.
+//!
+//! [`Food`] is eaten with [`Action::Eat`] next to it, applying its list of
+//! [`FoodEffect`]s. Every food [`Item`](crate::demo::pickup::Item) becomes
+//! [`Food`] when it is placed. Timed effects become
+//! [`Buff`]s, shown as icons on the HUD. The goose also slowly gets hungry
+//! and starts losing health when starving, unless hunger is turned off in
+//! the [`Settings`].
+
+use bevy::{
+    image::{ImageLoaderSettings, ImageSampler},
+    prelude::*,
+};
 
 use crate::{
+    AppSystems, PausableSystems,
+    asset_tracking::LoadResource,
     audio::sound_effect,
     demo::{
+        curse::CurseLevel,
         gun::{self, Gun},
-        level::CurseLevel,
+        health::Health,
         movement::MovementController,
         player::{Player, PlayerAssets},
     },
+    input::{Action, ActionState},
+    settings::Settings,
+    theme::{style::ThemedNode, widget::{self, Meter}},
 };
 
 pub(super) fn plugin(app: &mut App) {
-    app.add_systems(Update, eat);
+    app.load_resource::<FoodAssets>();
+    app.add_systems(
+        Update,
+        (
+            tick_buffs.in_set(AppSystems::TickTimers),
+            eat.in_set(AppSystems::RecordInput),
+            (
+                apply_buffs,
+                starve.run_if(hunger_enabled),
+                update_buff_icons,
+                update_hunger_meter,
+            )
+                .in_set(AppSystems::Update),
+        )
+            .in_set(PausableSystems),
+    );
+    app.add_observer(apply_food_effects);
+}
+
+#[derive(Resource, Asset, Clone, Reflect)]
+#[reflect(Resource)]
+pub struct FoodAssets {
+    #[dependency]
+    speed_icon: Handle<Image>,
+    #[dependency]
+    jump_icon: Handle<Image>,
+}
+
+impl FromWorld for FoodAssets {
+    fn from_world(world: &mut World) -> Self {
+        let assets = world.resource::<AssetServer>();
+        let pixel_art = |settings: &mut ImageLoaderSettings| {
+            settings.sampler = ImageSampler::nearest();
+        };
+        Self {
+            speed_icon: assets.load_with_settings("images/icons/speed.png", pixel_art),
+            jump_icon: assets.load_with_settings("images/icons/jump.png", pixel_art),
+        }
+    }
+}
+
+#[derive(Reflect, Debug, Clone, Copy, PartialEq)]
+pub enum FoodEffect {
+    Heal(f32),
+    /// Multiply a stat for a while. Eating the same kind again restarts it.
+    Buff {
+        kind: BuffKind,
+        multiplier: f32,
+        secs: f32,
+    },
+    /// Let the goose glide for its full duration again.
+    RefillGlide,
+    /// Raise the curse, or lower it when negative.
+    Curse(i32),
+    /// Fill up the hunger meter by this fraction.
+    Nourish(f32),
+    GiveGun {
+        ammo: u32,
+    },
 }
 
+/// Something the goose can eat.
 #[derive(Component, Reflect, Debug)]
 #[reflect(Component)]
 pub struct Food {
-    pub gives_gun: bool,
+    #[reflect(ignore)]
+    pub effects: &'static [FoodEffect],
+}
+
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuffKind {
+    Speed,
+    Jump,
+}
+
+impl BuffKind {
+    const ALL: [Self; 2] = [Self::Speed, Self::Jump];
+
+    fn icon(self, food_assets: &FoodAssets) -> Handle<Image> {
+        match self {
+            Self::Speed => food_assets.speed_icon.clone(),
+            Self::Jump => food_assets.jump_icon.clone(),
+        }
+    }
+}
+
+#[derive(Reflect, Debug, Clone)]
+pub struct Buff {
+    pub kind: BuffKind,
+    pub multiplier: f32,
+    pub timer: Timer,
+}
+
+/// Temporary stat changes from food.
+#[derive(Component, Reflect, Debug, Default)]
+#[reflect(Component)]
+pub struct Buffs(pub Vec<Buff>);
+
+impl Buffs {
+    /// Combined multiplier of all active buffs of a kind.
+    pub fn multiplier(&self, kind: BuffKind) -> f32 {
+        self.0
+            .iter()
+            .filter(|buff| buff.kind == kind)
+            .map(|buff| buff.multiplier)
+            .product()
+    }
+
+    fn get(&self, kind: BuffKind) -> Option<&Buff> {
+        self.0.iter().find(|buff| buff.kind == kind)
+    }
+}
+
+/// How full the goose is, between 0 (starving) and 1.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct Hunger {
+    pub fullness: f32,
+}
+
+impl Hunger {
+    /// Fullness lost per second.
+    const DRAIN: f32 = 1.0 / 150.0;
+    /// Health lost per second while starving.
+    const STARVING_DAMAGE: f32 = 3.0;
+}
+
+impl Default for Hunger {
+    fn default() -> Self {
+        Self { fullness: 1.0 }
+    }
+}
+
+fn hunger_enabled(settings: Res<Settings>) -> bool {
+    settings.hunger
+}
+
+/// Triggered on the entity that ate something.
+#[derive(EntityEvent, Debug)]
+pub struct Ate {
+    pub entity: Entity,
+    pub effects: &'static [FoodEffect],
 }
 
 fn eat(
     mut commands: Commands,
     player_assets: If<Res<PlayerAssets>>,
-    input: Res<ButtonInput<KeyCode>>,
-    food_query: Query<(&Transform, &Food, Entity)>,
-    player_query: Query<(&Transform, &mut Gun, &MovementController), With<Player>>,
-    mut curse_level: If<ResMut<CurseLevel>>,
+    actions: Res<ActionState>,
+    food_query: Query<(Entity, &Transform, &Food)>,
+    player_query: Query<(Entity, &Transform, &MovementController), With<Player>>,
 ) {
-    if !input.just_pressed(KeyCode::KeyE) {
+    if !actions.just_pressed(Action::Eat) {
         return;
     }
-    for (player_transform, mut gun, movement) in player_query {
-        for (food_transform, food, entity) in food_query {
-            if player_transform
-                .translation
-                .distance(food_transform.translation)
-                < 64.0
-            {
-                if food.gives_gun {
-                    curse_level.value = 1;
-                    curse_level.needs_change = true;
+    let Ok((player, player_transform, movement)) = player_query.single() else {
+        return;
+    };
+    for (entity, transform, food) in &food_query {
+        if player_transform.translation.distance(transform.translation) >= 64.0 {
+            continue;
+        }
+        if food
+            .effects
+            .iter()
+            .any(|effect| matches!(effect, FoodEffect::GiveGun { .. }))
+        {
+            // The goose can't help trying its new toy right away.
+            gun::spawn_bullet(
+                &mut commands,
+                player_transform.translation,
+                movement.facing_right,
+                &player_assets,
+            );
+        }
+        commands.trigger(Ate {
+            entity: player,
+            effects: food.effects,
+        });
+        commands.spawn(sound_effect(player_assets.honk.first().unwrap().clone()));
+        commands.entity(entity).despawn();
+    }
+}
+
+fn apply_food_effects(
+    ate: On<Ate>,
+    mut eater_query: Query<(
+        &mut Health,
+        &mut MovementController,
+        Option<&mut Buffs>,
+        Option<&mut Hunger>,
+        Option<&mut Gun>,
+    )>,
+    mut curse_level: If<ResMut<CurseLevel>>,
+) {
+    let Ok((mut health, mut movement, mut buffs, mut hunger, mut gun)) =
+        eater_query.get_mut(ate.entity)
+    else {
+        return;
+    };
+    for effect in ate.effects {
+        match *effect {
+            FoodEffect::Heal(amount) => {
+                health.current = (health.current + amount).min(health.max);
+            }
+            FoodEffect::Buff {
+                kind,
+                multiplier,
+                secs,
+            } => {
+                if let Some(buffs) = buffs.as_mut() {
+                    buffs.0.retain(|buff| buff.kind != kind);
+                    buffs.0.push(Buff {
+                        kind,
+                        multiplier,
+                        timer: Timer::from_seconds(secs, TimerMode::Once),
+                    });
+                }
+            }
+            FoodEffect::RefillGlide => movement.jump_timer = 0.0,
+            FoodEffect::Curse(amount) => curse_level.add(amount),
+            FoodEffect::Nourish(amount) => {
+                if let Some(hunger) = hunger.as_mut() {
+                    hunger.fullness = (hunger.fullness + amount).min(1.0);
+                }
+            }
+            FoodEffect::GiveGun { ammo } => {
+                if let Some(gun) = gun.as_mut() {
                     gun.enabled = true;
+                    gun.ammo += ammo;
                     gun.shooting_cooldown.reset();
-                    gun::spawn_bullet(
-                        &mut commands,
-                        player_transform.translation,
-                        movement.facing_right,
-                        &player_assets,
-                    );
                 }
-                commands.spawn(sound_effect(player_assets.honk.first().unwrap().clone()));
-                commands.get_entity(entity).unwrap().despawn();
             }
         }
     }
 }
+
+fn tick_buffs(time: Res<Time>, mut buffs_query: Query<&mut Buffs>) {
+    for mut buffs in &mut buffs_query {
+        for buff in &mut buffs.0 {
+            buff.timer.tick(time.delta());
+        }
+        buffs.0.retain(|buff| !buff.timer.is_finished());
+    }
+}
+
+fn apply_buffs(mut buffs_query: Query<(&Buffs, &mut MovementController)>) {
+    for (buffs, mut movement) in &mut buffs_query {
+        movement.speed_multiplier = buffs.multiplier(BuffKind::Speed);
+        movement.jump_multiplier = buffs.multiplier(BuffKind::Jump);
+    }
+}
+
+fn starve(time: Res<Time>, mut hunger_query: Query<(&mut Hunger, &mut Health), With<Player>>) {
+    for (mut hunger, mut health) in &mut hunger_query {
+        hunger.fullness = (hunger.fullness - Hunger::DRAIN * time.delta_secs()).max(0.0);
+        if hunger.fullness <= 0.0 {
+            health.current -= Hunger::STARVING_DAMAGE * time.delta_secs();
+        }
+    }
+}
+
+/// Shows one kind of buff while it is active.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct BuffIcon(BuffKind);
+
+/// Shrinks as a buff runs out.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct BuffTimeLeft(BuffKind);
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct HungerMeter;
+
+/// Row of icons for the player's active buffs.
+pub fn buff_bar(food_assets: &FoodAssets) -> impl Bundle {
+    let icons = BuffKind::ALL.map(|kind| {
+        (
+            Name::new(format!("{kind:?} Buff Icon")),
+            BuffIcon(kind),
+            Node {
+                flex_direction: FlexDirection::Column,
+                row_gap: px(2),
+                display: Display::None,
+                ..default()
+            },
+            children![
+                (
+                    ImageNode::new(kind.icon(food_assets)),
+                    Node {
+                        width: px(32),
+                        height: px(32),
+                        ..default()
+                    },
+                ),
+                (
+                    BuffTimeLeft(kind),
+                    ThemedNode::Marker,
+                    Node {
+                        width: percent(100),
+                        height: px(3),
+                        ..default()
+                    },
+                ),
+            ],
+        )
+    });
+    (
+        Name::new("Buff Bar"),
+        Node {
+            column_gap: px(6),
+            ..default()
+        },
+        Children::spawn(SpawnIter(icons.into_iter())),
+    )
+}
+
+/// Meter for how full the goose is, hidden while hunger is turned off.
+pub fn hunger_meter() -> impl Bundle {
+    (
+        HungerMeter,
+        widget::meter(
+            "Hunger Meter",
+            px(120),
+            px(10),
+            Color::srgb(0.85, 0.6, 0.25),
+        ),
+    )
+}
+
+fn update_buff_icons(
+    buffs_query: Query<&Buffs, With<Player>>,
+    mut icon_query: Query<(&BuffIcon, &mut Node), Without<BuffTimeLeft>>,
+    mut time_left_query: Query<(&BuffTimeLeft, &mut Node), Without<BuffIcon>>,
+) {
+    let Ok(buffs) = buffs_query.single() else {
+        return;
+    };
+    for (icon, mut node) in &mut icon_query {
+        let display = if buffs.get(icon.0).is_some() {
+            Display::Flex
+        } else {
+            Display::None
+        };
+        if node.display != display {
+            node.display = display;
+        }
+    }
+    for (time_left, mut node) in &mut time_left_query {
+        if let Some(buff) = buffs.get(time_left.0) {
+            node.width = percent(buff.timer.fraction_remaining() * 100.0);
+        }
+    }
+}
+
+fn update_hunger_meter(
+    settings: Res<Settings>,
+    hunger_query: Query<&Hunger, With<Player>>,
+    mut meter_query: Query<(&mut Node, &mut Meter), With<HungerMeter>>,
+) {
+    let fullness = hunger_query.single().map_or(1.0, |hunger| hunger.fullness);
+    for (mut node, mut meter) in &mut meter_query {
+        let display = if settings.hunger {
+            Display::Flex
+        } else {
+            Display::None
+        };
+        if node.display != display {
+            node.display = display;
+        }
+        if meter.fraction != fullness {
+            meter.fraction = fullness;
+        }
+    }
+}
@@ -1,11 +1,11 @@
-use std::{default, f32::consts::PI};
+use std::f32::consts::PI;
 
-use bevy::{ecs::event::SetEntityEventTarget, prelude::*};
+use bevy::prelude::*;
 
 use crate::{
     audio::sound_effect,
     demo::{
-        aabb::AABB, boss::Boss, enemy::Enemy, events::LevelUpEvent, level::CurseLevel, movement::MovementController, platform::Platform, player::PlayerAssets
+        aabb::{AABB, Hitbox}, boss::Boss, enemy::Enemy, health::Health, movement::MovementController, platform::Platform, player::PlayerAssets
     },
 };
 
@@ -21,6 +21,13 @@ pub struct Gun {
     pub can_shoot: bool,
     pub shooting: bool,
     pub enabled: bool,
+    /// Shots left. The gun stays silent when empty.
+    pub ammo: u32,
+}
+
+impl Gun {
+    /// Seconds between shots unless the curse says otherwise.
+    pub const DEFAULT_COOLDOWN: f32 = 0.8;
 }
 
 fn shoot(
@@ -40,8 +47,9 @@ fn shoot(
             }
             continue;
         }
-        if gun.can_shoot || gun.shooting_cooldown.just_finished() {
+        if gun.ammo > 0 && (gun.can_shoot || gun.shooting_cooldown.just_finished()) {
             gun.can_shoot = false;
+            gun.ammo -= 1;
             spawn_bullet(
                 &mut commands,
                 transform.translation,
@@ -116,10 +124,9 @@ fn handle_collisions(
     mut commands: Commands,
     time: Res<Time>,
     platform_query: Query<&AABB, With<Platform>>,
-    mut enemy_query: Query<(&Transform, &mut Enemy, Entity), (Without<Bullet>, Without<Boss>)>,
-    mut boss_query: Query<(&Transform, &mut Boss, Entity), (Without<Bullet>, Without<Enemy>)>,
+    mut enemy_query: Query<(&Transform, &Hitbox, &mut Health), (With<Enemy>, Without<Bullet>, Without<Boss>)>,
+    mut boss_query: Query<(&Transform, &Hitbox, &mut Health), (With<Boss>, Without<Bullet>, Without<Enemy>)>,
     mut bullet_query: Query<(&Transform, &mut Bullet, Entity), (Without<Enemy>, Without<Boss>)>,
-    mut curse_level: If<ResMut<CurseLevel>>,
 ) {
     'bullet: for (bullet_transform, mut bullet, bullet_entity) in bullet_query {
         bullet.despawn_timer.tick(time.delta());
@@ -136,36 +143,20 @@ fn handle_collisions(
                 continue 'bullet;
             }
         }
-        for (enemy_transform, mut enemy, enemy_entity) in &mut enemy_query {
-            let enemy_aabb = AABB::new(
-                enemy_transform.translation.xy(),
-                enemy_transform.scale.xy() * 16.0,
-            );
+        for (enemy_transform, hitbox, mut health) in &mut enemy_query {
+            let enemy_aabb = hitbox.aabb(enemy_transform);
             let depth = enemy_aabb.get_intersection_depth(&bullet_aabb);
             if depth != Vec2::ZERO {
-                enemy.health -= 50.0;
-                if enemy.health <= 0.0 {
-                    commands.get_entity(enemy_entity).unwrap().despawn();
-                    curse_level.value += 1;
-                    curse_level.needs_change = true;
-                }
+                health.current -= 50.0;
                 commands.get_entity(bullet_entity).unwrap().despawn();
                 continue 'bullet;
             }
         }
-        for (boss_transform, mut boss, boss_entity) in &mut boss_query {
-            let boss_aabb = AABB::new(
-                boss_transform.translation.xy(),
-                boss_transform.scale.xy() * 16.0,
-            );
+        for (boss_transform, hitbox, mut health) in &mut boss_query {
+            let boss_aabb = hitbox.aabb(boss_transform);
             let depth = boss_aabb.get_intersection_depth(&bullet_aabb);
             if depth != Vec2::ZERO {
-                boss.health -= 50.0;
-                if boss.health <= 0.0 {
-                    commands.get_entity(boss_entity).unwrap().despawn();
-                    curse_level.value += 100;
-                    curse_level.needs_change = true;
-                }
+                health.current -= 50.0;
                 commands.get_entity(bullet_entity).unwrap().despawn();
                 continue 'bullet;
             }
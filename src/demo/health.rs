@@ -0,0 +1,53 @@
+//! Health shared by everything that can be hurt: the player, enemies and bosses.
+//!
+//! Damage sources only lower [`Health::current`]. What happens on death is
+//! decided by observers of [`Died`] in the module owning the entity.
+
+use bevy::prelude::*;
+
+use crate::{AppSystems, PausableSystems};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        check_deaths
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems),
+    );
+}
+
+#[derive(Component, Reflect, Debug, Clone, Copy, PartialEq)]
+#[reflect(Component)]
+pub struct Health {
+    pub current: f32,
+    pub max: f32,
+}
+
+impl Health {
+    pub fn new(max: f32) -> Self {
+        Self { current: max, max }
+    }
+
+    /// Remaining health between 0 and 1.
+    pub fn fraction(&self) -> f32 {
+        (self.current / self.max).clamp(0.0, 1.0)
+    }
+
+    pub fn is_dead(&self) -> bool {
+        self.current <= 0.0
+    }
+}
+
+/// Triggered once for an entity whose [`Health`] dropped to zero.
+#[derive(EntityEvent, Debug)]
+pub struct Died {
+    pub entity: Entity,
+}
+
+fn check_deaths(mut commands: Commands, health_query: Query<(Entity, &Health), Changed<Health>>) {
+    for (entity, health) in &health_query {
+        if health.is_dead() {
+            commands.trigger(Died { entity });
+        }
+    }
+}
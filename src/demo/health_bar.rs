@@ -0,0 +1,329 @@
+//! Health bars for enemies and the boss.
+//!
+//! A [`HealthBar`] follows the [`Health`] of the entity it belongs to. Lost
+//! health first turns into a pale "chip" segment that drains after a short
+//! delay, so big hits stay readable. Enemies get a small bar floating above
+//! them once they are hurt; the boss gets a wide bar at the top of the screen
+//! while the fight is on.
+
+use bevy::prelude::*;
+
+use crate::{
+    AppSystems, PausableSystems,
+    demo::{
+        aabb::Hitbox,
+        boss::{Boss, PHASES},
+        enemy::Enemy,
+        health::Health,
+    },
+    localization::Localized,
+    screens::Screen,
+    settings::Settings,
+    theme::{
+        palette::{HEALTH_BAR_BACKGROUND, HEALTH_BAR_CHIP, HEALTH_BAR_FILL},
+        style::{ThemedNode, ThemedText},
+    },
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        (
+            tick_chip_delay.in_set(AppSystems::TickTimers),
+            (
+                spawn_boss_health_bar,
+                track_health,
+                (update_world_bars, update_ui_bars, follow_targets),
+            )
+                .chain()
+                .in_set(AppSystems::Update),
+        )
+            .in_set(PausableSystems),
+    );
+    app.add_systems(Update, color_bar_segments.in_set(AppSystems::Update));
+    app.add_observer(spawn_enemy_health_bar);
+}
+
+/// The entity whose health a bar shows.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+#[relationship(relationship_target = HealthBars)]
+pub struct HealthBarOf(pub Entity);
+
+/// Bars showing this entity's health. They are despawned along with it.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+#[relationship_target(relationship = HealthBarOf, linked_spawn)]
+pub struct HealthBars(Vec<Entity>);
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct HealthBar {
+    /// Health fraction currently shown.
+    pub health: f32,
+    /// End of the chip segment, never below `health`.
+    pub chip: f32,
+    /// Time after the last hit before the chip starts draining.
+    chip_delay: Timer,
+}
+
+impl HealthBar {
+    /// Chip drained per second, as a fraction of the whole bar.
+    const CHIP_DRAIN: f32 = 0.6;
+
+    fn new() -> Self {
+        Self {
+            health: 1.0,
+            chip: 1.0,
+            chip_delay: Timer::from_seconds(0.5, TimerMode::Once),
+        }
+    }
+}
+
+/// Part of a [`HealthBar`], sized from the bar it is a descendant of.
+#[derive(Component, Reflect, Debug, Clone, Copy, PartialEq, Eq)]
+#[reflect(Component)]
+enum BarSegment {
+    Fill,
+    Chip,
+}
+
+/// A bar in the world, kept above its target.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct WorldHealthBar;
+
+impl WorldHealthBar {
+    const SIZE: Vec2 = Vec2::new(48.0, 5.0);
+}
+
+fn spawn_enemy_health_bar(add: On<Add, Enemy>, mut commands: Commands) {
+    let size = WorldHealthBar::SIZE;
+    commands.spawn((
+        Name::new("Enemy Health Bar"),
+        HealthBarOf(add.entity),
+        HealthBar::new(),
+        WorldHealthBar,
+        Transform::default(),
+        // Only revealed once the enemy takes damage.
+        Visibility::Hidden,
+        children![
+            (
+                Sprite::from_color(HEALTH_BAR_BACKGROUND, size + Vec2::splat(2.0)),
+                Transform::from_xyz(0.0, 0.0, 0.0),
+            ),
+            (
+                BarSegment::Chip,
+                Sprite::from_color(HEALTH_BAR_CHIP, size),
+                Transform::from_xyz(0.0, 0.0, 0.1),
+            ),
+            (
+                BarSegment::Fill,
+                Sprite::from_color(HEALTH_BAR_FILL, size),
+                Transform::from_xyz(0.0, 0.0, 0.2),
+            ),
+        ],
+    ));
+}
+
+/// Show the boss bar at the top of the screen once the fight has started.
+fn spawn_boss_health_bar(
+    mut commands: Commands,
+    boss_query: Query<(Entity, &Boss), Without<HealthBars>>,
+) {
+    for (entity, boss) in &boss_query {
+        if !boss.is_engaged() {
+            continue;
+        }
+        let markers = PHASES.iter().skip(1).map(|phase| {
+            (
+                Name::new("Phase Marker"),
+                Node {
+                    position_type: PositionType::Absolute,
+                    left: percent(phase.health_below * 100.0),
+                    width: px(3),
+                    height: percent(100),
+                    ..default()
+                },
+                ThemedNode::Marker,
+            )
+        });
+        let segment_node = Node {
+            position_type: PositionType::Absolute,
+            width: percent(100),
+            height: percent(100),
+            ..default()
+        };
+        commands.spawn((
+            Name::new("Boss Health Bar"),
+            HealthBarOf(entity),
+            HealthBar::new(),
+            Node {
+                position_type: PositionType::Absolute,
+                top: px(24),
+                left: percent(20),
+                width: percent(60),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                row_gap: px(6),
+                ..default()
+            },
+            Pickable::IGNORE,
+            DespawnOnExit(Screen::Gameplay),
+            children![
+                (
+                    Name::new("Boss Name"),
+                    Text::new(Boss::NAME),
+                    Localized::new(Boss::NAME),
+                    ThemedText::Label,
+                ),
+                (
+                    Name::new("Boss Health Frame"),
+                    ThemedNode::Meter,
+                    Node {
+                        width: percent(100),
+                        height: px(18),
+                        border: UiRect::all(px(2)),
+                        ..default()
+                    },
+                    // Markers for where the next phases start go on top of the segments.
+                    Children::spawn((
+                        Spawn((
+                            BarSegment::Chip,
+                            segment_node.clone(),
+                            BackgroundColor(HEALTH_BAR_CHIP),
+                        )),
+                        Spawn((
+                            BarSegment::Fill,
+                            segment_node,
+                            BackgroundColor(HEALTH_BAR_FILL),
+                        )),
+                        SpawnIter(markers),
+                    )),
+                ),
+            ],
+        ));
+    }
+}
+
+fn tick_chip_delay(time: Res<Time>, mut bar_query: Query<&mut HealthBar>) {
+    for mut bar in &mut bar_query {
+        bar.chip_delay.tick(time.delta());
+    }
+}
+
+fn track_health(
+    time: Res<Time>,
+    mut bar_query: Query<(&HealthBarOf, &mut HealthBar)>,
+    health_query: Query<&Health>,
+) {
+    for (health_bar_of, mut bar) in &mut bar_query {
+        let Ok(health) = health_query.get(health_bar_of.0) else {
+            continue;
+        };
+        let fraction = health.fraction();
+        if fraction < bar.health {
+            bar.chip_delay.reset();
+        }
+        bar.health = fraction;
+        if bar.chip < fraction {
+            // Healing fills the bar straight away.
+            bar.chip = fraction;
+        } else if bar.chip_delay.is_finished() {
+            bar.chip = (bar.chip - HealthBar::CHIP_DRAIN * time.delta_secs()).max(fraction);
+        }
+    }
+}
+
+/// Length of a segment as a fraction of the whole bar.
+fn segment_length(segment: BarSegment, bar: &HealthBar) -> f32 {
+    match segment {
+        BarSegment::Fill => bar.health,
+        BarSegment::Chip => bar.chip,
+    }
+}
+
+fn update_world_bars(
+    mut bar_query: Query<(&HealthBar, &mut Visibility), With<WorldHealthBar>>,
+    mut segment_query: Query<(&BarSegment, &ChildOf, &mut Transform)>,
+) {
+    for (bar, mut visibility) in &mut bar_query {
+        visibility.set_if_neq(if bar.chip < 1.0 {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        });
+    }
+    for (segment, child_of, mut transform) in &mut segment_query {
+        let Ok((bar, _)) = bar_query.get(child_of.parent()) else {
+            continue;
+        };
+        let length = segment_length(*segment, bar);
+        // Keep the segment anchored to the left edge while it shrinks.
+        transform.scale.x = length;
+        transform.translation.x = (length - 1.0) * WorldHealthBar::SIZE.x / 2.0;
+    }
+}
+
+fn update_ui_bars(
+    bar_query: Query<&HealthBar, Without<WorldHealthBar>>,
+    parent_query: Query<&ChildOf>,
+    mut segment_query: Query<(Entity, &BarSegment, &mut Node)>,
+) {
+    for (entity, segment, mut node) in &mut segment_query {
+        let Some(bar) = parent_query
+            .iter_ancestors(entity)
+            .find_map(|ancestor| bar_query.get(ancestor).ok())
+        else {
+            continue;
+        };
+        node.width = percent(segment_length(*segment, bar) * 100.0);
+    }
+}
+
+/// Color segments for the [`ColorblindMode`](crate::settings::ColorblindMode)
+/// when they spawn or the setting changes.
+fn color_bar_segments(
+    settings: Res<Settings>,
+    mut segment_query: Query<(
+        Ref<BarSegment>,
+        Option<&mut Sprite>,
+        Option<&mut BackgroundColor>,
+    )>,
+) {
+    let (fill, chip) = settings.colorblind_mode.health_bar_colors();
+    for (segment, sprite, background) in &mut segment_query {
+        if !settings.is_changed() && !segment.is_added() {
+            continue;
+        }
+        let color = match *segment {
+            BarSegment::Fill => fill,
+            BarSegment::Chip => chip,
+        };
+        if let Some(mut sprite) = sprite {
+            sprite.color = color;
+        }
+        if let Some(mut background) = background {
+            background.0 = color;
+        }
+    }
+}
+
+/// Keep world-space bars just above the top of their target's hitbox.
+fn follow_targets(
+    mut bar_query: Query<(&HealthBarOf, &mut Transform), With<WorldHealthBar>>,
+    target_query: Query<(&Transform, Option<&Hitbox>), Without<WorldHealthBar>>,
+) {
+    for (health_bar_of, mut transform) in &mut bar_query {
+        let Ok((target_transform, hitbox)) = target_query.get(health_bar_of.0) else {
+            continue;
+        };
+        let top = hitbox
+            .copied()
+            .unwrap_or_default()
+            .aabb(target_transform)
+            .half_size
+            .y;
+        transform.translation = target_transform.translation + Vec3::new(0.0, top + 12.0, 10.0);
+    }
+}
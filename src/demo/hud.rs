@@ -0,0 +1,317 @@
+//! The gameplay HUD.
+//!
+//! Health, weapon and the run timer sit along the top of the screen, the curse
+//! meter below health, and buffs, hunger and glide stamina in the bottom
+//! corner. The HUD is hidden while the game is paused or a cutscene plays.
+
+use bevy::{prelude::*, time::Stopwatch};
+
+use crate::{
+    AppSystems, PausableSystems, Pause,
+    demo::{
+        corruption::curse_meter,
+        curse::{CurseAssets, CurseRules},
+        cutscene::Cutscene,
+        food::{FoodAssets, buff_bar, hunger_meter},
+        gun::Gun,
+        health::Health,
+        movement::MovementController,
+        pickup::{Item, PickupAssets},
+        player::Player,
+    },
+    localization::Localization,
+    screens::Screen,
+    settings::Settings,
+    theme::{
+        palette::HEALTH_BAR_FILL,
+        widget::{self, Meter, MeterFill},
+    },
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<RunTimer>();
+    app.add_systems(OnEnter(Screen::Gameplay), (reset_run_timer, spawn_hud));
+    app.add_systems(
+        Update,
+        (
+            tick_run_timer
+                .in_set(AppSystems::TickTimers)
+                .in_set(PausableSystems),
+            (
+                update_hud_visibility,
+                update_health_meter,
+                update_weapon,
+                update_glide_meter,
+                update_run_timer,
+            )
+                .in_set(AppSystems::Update),
+        )
+            .run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+/// Time spent in the current run, not counting pauses.
+#[derive(Resource, Reflect, Debug, Default)]
+#[reflect(Resource)]
+pub struct RunTimer(pub Stopwatch);
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct Hud;
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct HealthMeter;
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct HealthText;
+
+/// Gun icon and ammo count, shown once the goose has a gun.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct WeaponDisplay;
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct AmmoText;
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct GlideMeter;
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct RunTimerText;
+
+fn reset_run_timer(mut run_timer: ResMut<RunTimer>) {
+    run_timer.0.reset();
+}
+
+fn tick_run_timer(time: Res<Time>, mut run_timer: ResMut<RunTimer>) {
+    run_timer.0.tick(time.delta());
+}
+
+fn spawn_hud(
+    mut commands: Commands,
+    curse_assets: If<Res<CurseAssets>>,
+    curse_rules: Res<Assets<CurseRules>>,
+    food_assets: If<Res<FoodAssets>>,
+    pickup_assets: If<Res<PickupAssets>>,
+) {
+    let tier_count = curse_rules
+        .get(&curse_assets.rules)
+        .map_or(0, |rules| rules.tiers.len());
+    commands.spawn((
+        Name::new("HUD"),
+        Hud,
+        Node {
+            position_type: PositionType::Absolute,
+            width: percent(100),
+            height: percent(100),
+            padding: UiRect::all(px(16)),
+            flex_direction: FlexDirection::Column,
+            justify_content: JustifyContent::SpaceBetween,
+            ..default()
+        },
+        Pickable::IGNORE,
+        DespawnOnExit(Screen::Gameplay),
+        children![
+            (
+                Name::new("HUD Top"),
+                Node {
+                    justify_content: JustifyContent::SpaceBetween,
+                    align_items: AlignItems::FlexStart,
+                    ..default()
+                },
+                children![
+                    (
+                        Name::new("HUD Status"),
+                        Node {
+                            flex_direction: FlexDirection::Column,
+                            row_gap: px(12),
+                            ..default()
+                        },
+                        children![
+                            (
+                                Name::new("Health"),
+                                Node {
+                                    flex_direction: FlexDirection::Column,
+                                    row_gap: px(4),
+                                    ..default()
+                                },
+                                children![
+                                    (HealthText, widget::small_label("")),
+                                    (
+                                        HealthMeter,
+                                        widget::meter(
+                                            "Health Meter",
+                                            px(200),
+                                            px(16),
+                                            HEALTH_BAR_FILL
+                                        ),
+                                    ),
+                                ],
+                            ),
+                            curse_meter(tier_count),
+                        ],
+                    ),
+                    (
+                        Name::new("HUD Run"),
+                        Node {
+                            flex_direction: FlexDirection::Column,
+                            align_items: AlignItems::FlexEnd,
+                            row_gap: px(8),
+                            ..default()
+                        },
+                        children![
+                            (RunTimerText, widget::label("")),
+                            (
+                                Name::new("Weapon"),
+                                WeaponDisplay,
+                                Node {
+                                    align_items: AlignItems::Center,
+                                    column_gap: px(6),
+                                    display: Display::None,
+                                    ..default()
+                                },
+                                children![
+                                    (
+                                        ImageNode::new(Item::Pistol.image(&pickup_assets)),
+                                        Node {
+                                            width: px(32),
+                                            height: px(32),
+                                            ..default()
+                                        },
+                                    ),
+                                    (AmmoText, widget::label("")),
+                                ],
+                            ),
+                        ],
+                    ),
+                ],
+            ),
+            (
+                Name::new("HUD Bottom"),
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::FlexStart,
+                    row_gap: px(8),
+                    ..default()
+                },
+                children![
+                    buff_bar(&food_assets),
+                    hunger_meter(),
+                    (
+                        GlideMeter,
+                        widget::meter("Glide Meter", px(120), px(6), Color::srgb(0.6, 0.85, 0.95)),
+                    ),
+                ],
+            ),
+        ],
+    ));
+}
+
+fn update_hud_visibility(
+    pause: Res<State<Pause>>,
+    cutscene: Res<State<Cutscene>>,
+    mut hud_query: Query<&mut Visibility, With<Hud>>,
+) {
+    let visibility = if pause.get().0 || cutscene.get().0 {
+        Visibility::Hidden
+    } else {
+        Visibility::Inherited
+    };
+    for mut hud_visibility in &mut hud_query {
+        hud_visibility.set_if_neq(visibility);
+    }
+}
+
+fn update_health_meter(
+    settings: Res<Settings>,
+    localization: Localization,
+    player_query: Query<&Health, With<Player>>,
+    mut meter_query: Query<(&mut Meter, &Children), With<HealthMeter>>,
+    mut fill_query: Query<&mut BackgroundColor, With<MeterFill>>,
+    mut text_query: Query<&mut Text, With<HealthText>>,
+) {
+    let Ok(health) = player_query.single() else {
+        return;
+    };
+    let (fill_color, _) = settings.colorblind_mode.health_bar_colors();
+    for (mut meter, children) in &mut meter_query {
+        if meter.fraction != health.fraction() {
+            meter.fraction = health.fraction();
+        }
+        let mut fills = fill_query.iter_many_mut(children);
+        while let Some(mut fill) = fills.fetch_next() {
+            fill.set_if_neq(BackgroundColor(fill_color));
+        }
+    }
+    for mut text in &mut text_query {
+        let label = localization.format(
+            "Health {current}/{max}",
+            &[
+                ("current", &format!("{:.0}", health.current.max(0.0))),
+                ("max", &format!("{:.0}", health.max)),
+            ],
+        );
+        if text.0 != label {
+            text.0 = label;
+        }
+    }
+}
+
+fn update_weapon(
+    gun_query: Query<&Gun, With<Player>>,
+    mut weapon_query: Query<&mut Node, With<WeaponDisplay>>,
+    mut ammo_query: Query<&mut Text, With<AmmoText>>,
+) {
+    let Ok(gun) = gun_query.single() else {
+        return;
+    };
+    for mut node in &mut weapon_query {
+        let display = if gun.enabled {
+            Display::Flex
+        } else {
+            Display::None
+        };
+        if node.display != display {
+            node.display = display;
+        }
+    }
+    for mut text in &mut ammo_query {
+        let label = format!("x {}", gun.ammo);
+        if text.0 != label {
+            text.0 = label;
+        }
+    }
+}
+
+fn update_glide_meter(
+    player_query: Query<&MovementController, With<Player>>,
+    mut meter_query: Query<&mut Meter, With<GlideMeter>>,
+) {
+    let Ok(movement) = player_query.single() else {
+        return;
+    };
+    let stamina = 1.0 - movement.jump_timer / movement.jump_time;
+    for mut meter in &mut meter_query {
+        if meter.fraction != stamina {
+            meter.fraction = stamina;
+        }
+    }
+}
+
+fn update_run_timer(
+    run_timer: Res<RunTimer>,
+    mut text_query: Query<&mut Text, With<RunTimerText>>,
+) {
+    let secs = run_timer.0.elapsed_secs();
+    let label = format!("{:02}:{:05.2}", (secs / 60.0) as u32, secs % 60.0);
+    for mut text in &mut text_query {
+        if text.0 != label {
+            text.0 = label.clone();
+        }
+    }
+}
@@ -1,48 +1,80 @@
 //! Spawn the main level.
+//!
+//! The ground and most of what's placed on it come from a map made in Tiled
+//! (`assets/levels/farm.tmj`, see [`tiled`](super::tiled)). Map objects are
+//! spawned by the constructor registered for their class in the
+//! [`LevelObjectRegistry`], and the rest of the level is set up in code.
+//! Saving the map while playing a native dev build respawns everything it
+//! placed. The camera is kept inside the map.
+
+use std::collections::HashMap;
 
 use bevy::{
+    ecs::system::SystemParam,
     image::{ImageLoaderSettings, ImageSampler},
     prelude::*,
 };
 
+#[cfg(feature = "dev_native")]
+use crate::demo::{
+    curse::{CurseLevel, CurseLevelChanged},
+    player::Player,
+};
 use crate::{
     asset_tracking::LoadResource,
     audio::music,
     demo::{
-        aabb::AABB, boss::{BossAssets, boss}, enemy::{EnemyAssets, mushroom}, food::Food, gun::Gun, platform::{Grass, Platform, PlatformAssets, platform}, player::{Player, PlayerAssets, player}
+        aabb::AABB,
+        boss::{BossAssets, boss},
+        camera::CameraBounds,
+        checkpoint::checkpoint,
+        corruption::Corruptible,
+        enemy::Enemies,
+        parallax::parallax_layer,
+        pickup::{Item, PickupAssets, pickup},
+        platform::Platform,
+        player::{PlayerAssets, player},
+        spawner::{SpawnEntry, Wave, arena, spawner},
+        tiled::{LevelMap, LevelObject},
+        tilemap::tile_layer,
     },
+    localization::Localized,
     screens::Screen,
 };
 
 pub(super) fn plugin(app: &mut App) {
     app.load_resource::<LevelAssets>();
-    app.insert_resource::<CurseLevel>(CurseLevel {
-        value: 0,
-        needs_change: false,
-    });
-    app.add_systems(Update, curse_level_change);
+    app.init_resource::<LevelObjectRegistry>();
+    #[cfg(feature = "dev_native")]
+    app.add_systems(Update, reload_level_map.run_if(in_state(Screen::Gameplay)));
 }
 
-#[derive(Resource)]
-pub struct CurseLevel {
-    pub value: u32,
-    pub needs_change: bool,
-}
+/// The level's background music, swapped out by the curse rules.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct LevelMusic;
 
 #[derive(Resource, Asset, Clone, Reflect)]
 #[reflect(Resource)]
 pub struct LevelAssets {
+    #[dependency]
+    map: Handle<LevelMap>,
     #[dependency]
     music: Handle<AudioSource>,
     barn: Handle<Image>,
-    hay: Handle<Image>,
-    pistol: Handle<Image>,
+    #[dependency]
+    sky: Handle<Image>,
+    #[dependency]
+    hills: Handle<Image>,
+    #[dependency]
+    fence: Handle<Image>,
 }
 
 impl FromWorld for LevelAssets {
     fn from_world(world: &mut World) -> Self {
         let assets = world.resource::<AssetServer>();
         Self {
+            map: assets.load("levels/farm.tmj"),
             music: assets.load("audio/music/Fluffing A Duck.ogg"),
             barn: assets.load_with_settings(
                 "images/barn.png",
@@ -50,14 +82,20 @@ impl FromWorld for LevelAssets {
                     settings.sampler = ImageSampler::nearest();
                 },
             ),
-            hay: assets.load_with_settings(
-                "images/hay.png",
+            sky: assets.load_with_settings(
+                "images/sky.png",
                 |settings: &mut ImageLoaderSettings| {
                     settings.sampler = ImageSampler::nearest();
                 },
             ),
-            pistol: assets.load_with_settings(
-                "images/pistol.png",
+            hills: assets.load_with_settings(
+                "images/hills.png",
+                |settings: &mut ImageLoaderSettings| {
+                    settings.sampler = ImageSampler::nearest();
+                },
+            ),
+            fence: assets.load_with_settings(
+                "images/fence.png",
                 |settings: &mut ImageLoaderSettings| {
                     settings.sampler = ImageSampler::nearest();
                 },
@@ -66,102 +104,265 @@ impl FromWorld for LevelAssets {
     }
 }
 
+/// Commands and assets for spawning what a level map places.
+#[derive(SystemParam)]
+pub struct LevelSpawner<'w, 's> {
+    pub commands: Commands<'w, 's>,
+    player_assets: If<Res<'w, PlayerAssets>>,
+    enemies: Enemies<'w>,
+    boss_assets: If<Res<'w, BossAssets>>,
+    pickup_assets: If<Res<'w, PickupAssets>>,
+    texture_atlas_layouts: ResMut<'w, Assets<TextureAtlasLayout>>,
+}
+
+/// Spawns a [`LevelObject`], returning the spawned entity.
+pub type SpawnLevelObject = fn(&mut LevelSpawner, &LevelObject) -> Result<Entity>;
+
+/// What each class of map object spawns as, by class name.
+#[derive(Resource)]
+pub struct LevelObjectRegistry(HashMap<String, SpawnLevelObject>);
+
+impl LevelObjectRegistry {
+    pub fn register(&mut self, class: impl Into<String>, spawn: SpawnLevelObject) {
+        self.0.insert(class.into(), spawn);
+    }
+
+    pub fn get(&self, class: &str) -> Option<SpawnLevelObject> {
+        self.0.get(class).copied()
+    }
+}
+
+impl Default for LevelObjectRegistry {
+    fn default() -> Self {
+        let mut registry = Self(HashMap::new());
+        registry.register("player", spawn_player);
+        registry.register("pickup", spawn_pickup);
+        registry.register("enemy", spawn_enemy);
+        registry.register("boss", spawn_boss);
+        registry.register("checkpoint", spawn_checkpoint);
+        registry.register("sign", spawn_sign);
+        registry
+    }
+}
+
+/// The goose. The `speed` property sets its walking speed.
+fn spawn_player(level_spawner: &mut LevelSpawner, object: &LevelObject) -> Result<Entity> {
+    let bundle = player(
+        object.property("speed").unwrap_or(400.0),
+        object.position(),
+        &level_spawner.player_assets,
+        &mut level_spawner.texture_atlas_layouts,
+    );
+    Ok(level_spawner.commands.spawn(bundle).id())
+}
+
+/// A pickup of the [`Item`] named by the `item` property, hay if unset.
+fn spawn_pickup(level_spawner: &mut LevelSpawner, object: &LevelObject) -> Result<Entity> {
+    let item = object.property("item").unwrap_or(Item::Hay);
+    let bundle = pickup(item, object.position(), &level_spawner.pickup_assets);
+    Ok(level_spawner.commands.spawn(bundle).id())
+}
+
+/// An enemy of the type in the [`EnemyRegistry`](super::enemy::EnemyRegistry)
+/// named by the `kind` property.
+fn spawn_enemy(level_spawner: &mut LevelSpawner, object: &LevelObject) -> Result<Entity> {
+    let kind: String = object
+        .property("kind")
+        .ok_or("enemies need a `kind` property")?;
+    let bundle = level_spawner
+        .enemies
+        .enemy(&kind, object.position().extend(4.0))?;
+    Ok(level_spawner.commands.spawn(bundle).id())
+}
+
+/// The boss, hovering in the middle of the object, which spans its arena.
+fn spawn_boss(level_spawner: &mut LevelSpawner, object: &LevelObject) -> Result<Entity> {
+    let bundle = boss(
+        object.position(),
+        object.rect.min.x,
+        object.rect.max.x,
+        &level_spawner.boss_assets,
+    );
+    Ok(level_spawner.commands.spawn(bundle).id())
+}
+
+/// A checkpoint whose flag stands on the object's point.
+fn spawn_checkpoint(level_spawner: &mut LevelSpawner, object: &LevelObject) -> Result<Entity> {
+    Ok(level_spawner
+        .commands
+        .spawn(checkpoint(object.position()))
+        .id())
+}
+
+/// The English `text` property, shown in the player's language.
+fn spawn_sign(level_spawner: &mut LevelSpawner, object: &LevelObject) -> Result<Entity> {
+    let text: String = object
+        .property("text")
+        .ok_or("signs need a `text` property")?;
+    Ok(level_spawner
+        .commands
+        .spawn((
+            Name::new("Sign"),
+            Text2d::default(),
+            // Follows the current binding of any action it names.
+            Localized::new(text),
+            Transform::from_translation(object.position().extend(10.0)),
+        ))
+        .id())
+}
+
+/// Marks what the level map spawned, to be replaced when the map is edited.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct FromLevelMap;
+
+/// Spawn the map's tile layers and objects, leaving out the player when
+/// `respawn_player` is false.
+fn spawn_level_map(
+    map: &LevelMap,
+    registry: &LevelObjectRegistry,
+    level_spawner: &mut LevelSpawner,
+    respawn_player: bool,
+) {
+    for layer in &map.tile_layers {
+        level_spawner.commands.spawn((
+            tile_layer(layer.clone(), 1.0),
+            FromLevelMap,
+            DespawnOnExit(Screen::Gameplay),
+        ));
+    }
+    for object in &map.objects {
+        if !respawn_player && object.class == "player" {
+            continue;
+        }
+        let Some(spawn) = registry.get(&object.class) else {
+            warn!(
+                "Skipping level object `{}` of unknown class `{}`",
+                object.name, object.class
+            );
+            continue;
+        };
+        match spawn(level_spawner, object) {
+            Ok(entity) => {
+                level_spawner
+                    .commands
+                    .entity(entity)
+                    .insert((FromLevelMap, DespawnOnExit(Screen::Gameplay)));
+            }
+            Err(error) => warn!("Skipping level object `{}`: {error}", object.name),
+        }
+    }
+}
+
 /// A system that spawns the main level.
 pub fn spawn_level(
-    mut commands: Commands,
     level_assets: If<Res<LevelAssets>>,
-    player_assets: If<Res<PlayerAssets>>,
-    platform_assets: If<Res<PlatformAssets>>,
-    enemy_assets: If<Res<EnemyAssets>>,
-    boss_assets: If<Res<BossAssets>>,
-    mut texture_atlas_layouts: If<ResMut<Assets<TextureAtlasLayout>>>,
-    mut meshes: If<ResMut<Assets<Mesh>>>,
-    mut materials: If<ResMut<Assets<ColorMaterial>>>,
+    level_maps: Res<Assets<LevelMap>>,
+    registry: Res<LevelObjectRegistry>,
+    mut level_spawner: LevelSpawner,
 ) {
+    if let Some(map) = level_maps.get(&level_assets.map) {
+        level_spawner
+            .commands
+            .insert_resource(CameraBounds(Some(map.bounds)));
+        spawn_level_map(map, &registry, &mut level_spawner, true);
+    }
+
+    let commands = &mut level_spawner.commands;
     commands.spawn((
         Name::new("Level"),
         Transform::default(),
         Visibility::default(),
         DespawnOnExit(Screen::Gameplay),
         children![
-            player(400.0, &player_assets, &mut texture_atlas_layouts, &mut meshes, &mut materials),
             (
                 Name::new("Gameplay Music"),
-                music(level_assets.music.clone())
+                music(level_assets.music.clone()),
+                LevelMusic,
             ),
             barn(&level_assets),
-            // Platforms
-            platform(
-                Vec2::new(120.0, -5.5),
-                Vec2::new(300.0, 1.0),
-                &platform_assets
-            ),
-            // Gun
-            (
-                Food { gives_gun: true },
-                Transform::from_xyz(3500.0, -300.0, 3.0).with_scale(Vec3::new(1.5, 1.5, 1.0)),
-                Sprite {
-                    image: level_assets.pistol.clone(),
-                    ..Default::default()
-                }
-            ),
-            // Enemies
-            mushroom(
-                250.0,
-                Vec3::new(5000.0, -200.0, 4.0),
-                5.0,
-                &enemy_assets,
-                &mut texture_atlas_layouts,
+            // Background, from farthest to nearest
+            parallax_layer(
+                "sky",
+                level_assets.sky.clone(),
+                Vec2::ZERO,
+                Vec2::ZERO,
+                8.0,
+                -100.0
             ),
-            boss(
-                Vec2::new(10_000.0, 0.0),
-                &boss_assets,
-                &mut meshes,
-                &mut materials,
+            parallax_layer(
+                "hills",
+                level_assets.hills.clone(),
+                Vec2::new(0.2, 0.4),
+                Vec2::new(0.0, -264.0),
+                4.0,
+                -90.0
             ),
-            (
-                Text2d::new("Quit the game already"),
-                Transform::from_xyz(12_500.0, 0.0, 10.0),
+            parallax_layer(
+                "fence",
+                level_assets.fence.clone(),
+                Vec2::new(0.6, 0.9),
+                Vec2::new(0.0, -302.0),
+                4.0,
+                -80.0
             ),
-            (
-                Text2d::new("You weren't supposed to see this..."),
-                Transform::from_xyz(17_500.0, 0.0, 10.0),
-            )
         ],
     ));
 
     commands.spawn((
-        Text2d::new("press Left Mouse Button to honk"),
-        Transform::from_xyz(0.0, -100.0, 10.0),
+        arena(
+            7400.0,
+            8800.0,
+            -200.0,
+            vec![
+                Wave::new(&[("fox", 2)]),
+                Wave::new(&[("mushroom", 2), ("crow", 1)]),
+                Wave::new(&[("fox", 2), ("crow", 2)]),
+            ],
+        ),
+        DespawnOnExit(Screen::Gameplay),
     ));
-    for i in 0..10 {
-        let x: f32 = 800.0 + (i as f32) * 200.0 + rand::random::<f32>() * 180.0;
-        if i == 0 {
-            commands.spawn((
-                Text2d::new("press E to eat"),
-                Transform::from_xyz(x, -100.0, 10.0),
-            ));
-        }
-        commands.spawn((
-            Food { gives_gun: false },
-            Transform::from_xyz(x, -300.0, 3.0).with_scale(Vec3::new(1.5, 1.5, 1.0)),
-            Sprite {
-                image: level_assets.hay.clone(),
-                ..Default::default()
-            },
-        ));
-    }
+    commands.spawn((
+        spawner(
+            Vec2::new(9300.0, -200.0),
+            vec![SpawnEntry::new("crow", 2.0), SpawnEntry::new("fox", 1.0)],
+            2,
+            4.0,
+            Some(5),
+        ),
+        DespawnOnExit(Screen::Gameplay),
+    ));
+}
 
-    for i in 0..15 {
-        let x: f32 = 5500.0 + (i as f32) * 100.0 + rand::random::<f32>() * 100.0;
-        commands.spawn(mushroom(
-            100.0,
-            Vec3::new(x, -200.0, 4.0),
-            rand::random::<f32>() + 3.0,
-            &enemy_assets,
-            &mut texture_atlas_layouts,
-        ));
+/// Respawn what the level map placed whenever it is edited, keeping the
+/// player where it is and the current curse tier's retextures.
+#[cfg(feature = "dev_native")]
+fn reload_level_map(
+    mut map_events: MessageReader<AssetEvent<LevelMap>>,
+    level_assets: If<Res<LevelAssets>>,
+    level_maps: Res<Assets<LevelMap>>,
+    registry: Res<LevelObjectRegistry>,
+    curse_level: Res<CurseLevel>,
+    map_entity_query: Query<Entity, (With<FromLevelMap>, Without<Player>)>,
+    player_query: Query<(), With<Player>>,
+    mut level_spawner: LevelSpawner,
+) {
+    let modified = map_events
+        .read()
+        .any(|event| event.is_modified(&level_assets.map));
+    let Some(map) = level_maps.get(&level_assets.map).filter(|_| modified) else {
+        return;
+    };
+    for entity in &map_entity_query {
+        level_spawner.commands.entity(entity).despawn();
     }
+    spawn_level_map(map, &registry, &mut level_spawner, player_query.is_empty());
+    // The new tile chunks use the base tileset, so re-apply the current tier
+    // without announcing it again.
+    level_spawner.commands.trigger(CurseLevelChanged {
+        previous: Some(curse_level.value),
+        value: curse_level.value,
+    });
 }
 
 fn barn(level_assets: &If<Res<LevelAssets>>) -> impl Bundle {
@@ -174,52 +375,10 @@ fn barn(level_assets: &If<Res<LevelAssets>>) -> impl Bundle {
             ..Default::default()
         },
         Platform,
+        Corruptible,
         AABB {
             center: Vec2::new(-6.0, -2.0) * 64.0,
             half_size: Vec2::new(2.0, 8.0) * 32.0,
         },
     )
 }
-
-fn curse_level_change(
-    mut commands: Commands,
-    grass_query: Query<&mut Sprite, With<Grass>>,
-    gun_query: Query<&mut Gun>,
-    player_query: Query<&Transform, With<Player>>,
-    platform_assets: If<Res<PlatformAssets>>,
-    mut curse_level: If<ResMut<CurseLevel>>,
-) {
-    if !curse_level.needs_change {
-        return;
-    }
-    curse_level.needs_change = false;
-    let image = match curse_level.value {
-        0 => &platform_assets.grass0,
-        1 => &platform_assets.grass1,
-        2..7 => &platform_assets.grass2,
-        _ => &platform_assets.grass3,
-    };
-    if curse_level.value >= 2 {
-        for mut gun in gun_query {
-            gun.shooting_cooldown = Timer::from_seconds(0.1, TimerMode::Repeating);
-        }
-    }
-    for mut sprite in grass_query {
-        sprite.image = image.clone();
-    }
-    if curse_level.value > 100 {
-        for player_transform in player_query {
-            commands.spawn(
-                (
-                    Text2d::new("THE END"),
-                    Transform::from_xyz(player_transform.translation.x, 0.0, 10.0),
-                    TextFont {
-                        font_size: 50.0,
-                        ..default()
-                    },
-                )
-            );
-        }
-
-    }
-}
\ No newline at end of file
@@ -6,27 +6,58 @@
 use bevy::prelude::*;
 
 mod aabb;
+mod ai;
 mod animation;
+mod camera;
+mod checkpoint;
+mod corruption;
+mod curse;
+pub mod cutscene;
 mod enemy;
 mod events;
+mod explosion;
 mod food;
 mod gun;
+mod health;
+mod health_bar;
+mod hud;
 pub mod level;
 mod movement;
+mod parallax;
+mod pickup;
 mod platform;
 pub mod player;
+mod spawner;
+mod tiled;
+mod tilemap;
 mod boss;
 
 pub(super) fn plugin(app: &mut App) {
     app.add_plugins((
+        ai::plugin,
         animation::plugin,
-        platform::plugin,
+        tiled::plugin,
         enemy::plugin,
         level::plugin,
         movement::plugin,
         gun::plugin,
         player::plugin,
-        food::plugin,
+        pickup::plugin,
         boss::plugin,
+        explosion::plugin,
+        food::plugin,
+        health::plugin,
+        health_bar::plugin,
+        spawner::plugin,
+    ));
+    // Plugin tuples are limited in size, so the rest are added separately.
+    app.add_plugins((
+        camera::plugin,
+        checkpoint::plugin,
+        cutscene::plugin,
+        curse::plugin,
+        corruption::plugin,
+        hud::plugin,
+        parallax::plugin,
     ));
 }
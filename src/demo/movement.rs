@@ -18,17 +18,17 @@ use bevy::prelude::*;
 use crate::{
     AppSystems, PausableSystems,
     demo::{
-        aabb::AABB,
-        enemy::{Explosion, Garlic},
+        aabb::{AABB, Hitbox},
+        enemy::Garlic,
+        explosion::Explosion,
         platform::Platform,
-        player::Player,
     },
 };
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(
         FixedUpdate,
-        (apply_movement, handle_collisions, apply_follow_camera)
+        (apply_movement, handle_collisions)
             .chain()
             .in_set(AppSystems::Update)
             .in_set(PausableSystems),
@@ -43,6 +43,9 @@ pub(super) fn plugin(app: &mut App) {
 pub struct MovementController {
     pub speed: f32,
     pub jump_force: f32,
+    /// Multipliers from temporary effects like food buffs.
+    pub speed_multiplier: f32,
+    pub jump_multiplier: f32,
     pub velocity: Vec2,
     pub gravity: f32,
     pub grounded: bool,
@@ -53,6 +56,12 @@ pub struct MovementController {
     pub gliding: bool,
 
     pub facing_right: bool,
+    /// Horizontal velocity from being pushed around, e.g. by explosions.
+    /// It is added on top of the walking speed and decays over time.
+    pub knockback: f32,
+    /// Fraction of the falling speed kept when bouncing off the ground.
+    /// Zero lands without bouncing.
+    pub bounciness: f32,
 }
 
 impl Default for MovementController {
@@ -60,6 +69,8 @@ impl Default for MovementController {
         Self {
             speed: 70.0,
             jump_force: 666.0,
+            speed_multiplier: 1.0,
+            jump_multiplier: 1.0,
             velocity: Vec2::ZERO,
             gravity: 100.0,
             grounded: false,
@@ -68,6 +79,8 @@ impl Default for MovementController {
             horizontal: 0.0,
             gliding: false,
             facing_right: true,
+            knockback: 0.0,
+            bounciness: 0.0,
         }
     }
 }
@@ -77,8 +90,11 @@ fn apply_movement(
     mut movement_query: Query<(&mut MovementController, &mut Transform)>,
 ) {
     let terminal_velocity = -1500.0;
+    let knockback_decay = (1.0 - 6.0 * time.delta_secs()).max(0.0);
     for (mut controller, mut transform) in &mut movement_query {
-        controller.velocity.x = controller.speed * controller.horizontal;
+        controller.velocity.x = controller.speed * controller.speed_multiplier * controller.horizontal
+            + controller.knockback;
+        controller.knockback *= knockback_decay;
         if !controller.grounded {
             if controller.gliding {
                 controller.velocity.y = -controller.gravity * 0.3;
@@ -92,35 +108,19 @@ fn apply_movement(
     }
 }
 
-#[derive(Component, Reflect)]
-#[reflect(Component)]
-pub struct FollowCamera;
-
-fn apply_follow_camera(
-    mut camera_query: Query<&mut Transform, With<Camera2d>>,
-    player_query: Query<&Transform, (With<FollowCamera>, Without<Camera2d>)>,
-) {
-    if let Ok(mut camera_transform) = camera_query.single_mut() {
-        if let Ok(player_transform) = player_query.single() {
-            // camera_transform.translation.x = camera_transform.translation.x.max(player_transform.translation.x);
-            camera_transform.translation.x = player_transform.translation.x;
-        }
-    }
-}
-
-fn handle_collisions(
+pub(super) fn handle_collisions(
     mut commands: Commands,
     platform_query: Query<&AABB, With<Platform>>,
     mut movement_query: Query<(
         &mut Transform,
         &mut MovementController,
         Entity,
+        Option<&Hitbox>,
         Option<&Garlic>,
     )>,
 ) {
-    for (mut movement_transform, mut movement, entity, garlic) in &mut movement_query {
-        let movement_size = movement_transform.scale.xy() * 16.0;
-        let mut movement_aabb = AABB::new(movement_transform.translation.xy(), movement_size);
+    for (mut movement_transform, mut movement, entity, hitbox, garlic) in &mut movement_query {
+        let mut movement_aabb = hitbox.copied().unwrap_or_default().aabb(&movement_transform);
         let mut collided = false;
         for platform_aabb in &platform_query {
             if movement_aabb.bottom() > platform_aabb.top() {
@@ -148,18 +148,23 @@ fn handle_collisions(
 
             if depth.y.abs() <= 24.0 {
                 movement_transform.translation.y += depth.y;
+                let landing_speed = -movement.velocity.y;
                 movement.velocity.y = 0.0;
 
                 if depth.y > 0.0 && depth.y <= 24.0 {
-                    movement.grounded = true;
-                    movement.jump_timer = 0.0;
+                    if landing_speed * movement.bounciness > 150.0 {
+                        movement.velocity.y = landing_speed * movement.bounciness;
+                    } else {
+                        movement.grounded = true;
+                        movement.jump_timer = 0.0;
+                    }
                 }
             }
         }
         if collided {
             if garlic.is_some() {
                 commands.spawn((
-                    Explosion { radius: 60.0 },
+                    Explosion::default(),
                     Transform::from_translation(movement_transform.translation),
                 ));
                 commands.get_entity(entity).unwrap().despawn();
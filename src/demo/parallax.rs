@@ -0,0 +1,98 @@
+//! Background layers scrolling slower than the world, giving it depth.
+//!
+//! Every [`ParallaxLayer`] is a single sprite tiling its texture horizontally,
+//! moved along with the camera each frame so it always covers the view. How
+//! far it lags behind the world is set by its scroll factor. Levels spawn their
+//! own layers, and the curse rules can swap a layer's texture by its name.
+
+use bevy::{prelude::*, transform::TransformSystems};
+
+use crate::viewport::{MainCamera, VirtualResolution};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        PostUpdate,
+        scroll_parallax_layers.before(TransformSystems::Propagate),
+    );
+}
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct ParallaxLayer {
+    /// Identifies the layer to [`CurseTarget::Parallax`](super::curse::CurseTarget::Parallax).
+    pub name: String,
+    /// How fast the layer scrolls relative to the world on each axis. 1 moves
+    /// with the world, 0 stays put on screen like the sky.
+    pub scroll: Vec2,
+    /// Center of the layer while the camera is at the world origin.
+    pub origin: Vec2,
+    /// Size of a texture pixel in world units.
+    pub scale: f32,
+}
+
+/// A background layer tiling `image`, drawn at depth `z`.
+pub fn parallax_layer(
+    name: &str,
+    image: Handle<Image>,
+    scroll: Vec2,
+    origin: Vec2,
+    scale: f32,
+    z: f32,
+) -> impl Bundle {
+    (
+        Name::new(format!("Parallax Layer {name}")),
+        ParallaxLayer {
+            name: name.to_string(),
+            scroll,
+            origin,
+            scale,
+        },
+        Transform::from_translation(origin.extend(z)),
+        Sprite {
+            image,
+            image_mode: SpriteImageMode::Tiled {
+                tile_x: true,
+                tile_y: false,
+                stretch_value: scale,
+            },
+            ..default()
+        },
+    )
+}
+
+fn scroll_parallax_layers(
+    resolution: Res<VirtualResolution>,
+    images: Res<Assets<Image>>,
+    camera_query: Query<(&Transform, &Projection), With<MainCamera>>,
+    mut layer_query: Query<(&ParallaxLayer, &mut Transform, &mut Sprite), Without<MainCamera>>,
+) {
+    let Ok((camera_transform, projection)) = camera_query.single() else {
+        return;
+    };
+    let view_scale = match projection {
+        Projection::Orthographic(orthographic) => orthographic.scale,
+        _ => 1.0,
+    };
+    let view_width = resolution.0.x as f32 * view_scale;
+    let camera = camera_transform.translation.truncate();
+
+    for (layer, mut transform, mut sprite) in &mut layer_query {
+        // Retextured layers may change size, so this is checked every frame.
+        let Some(image) = images.get(&sprite.image) else {
+            continue;
+        };
+        let tile_size = image.size_f32() * layer.scale;
+        let offset = layer.origin + camera * (Vec2::ONE - layer.scroll);
+        // Tiles start at the sprite's left edge, so centering an even number of
+        // them on a tile edge keeps the texture in place as the sprite moves.
+        // One tile to spare on either side keeps the sprite's ends out of view.
+        let tiles = ((view_width / tile_size.x).ceil() as u32 + 2).next_multiple_of(2);
+        transform.translation.x = camera.x - (camera.x - offset.x).rem_euclid(tile_size.x);
+        transform.translation.y = offset.y;
+        let size = Vec2::new(tiles as f32 * tile_size.x, tile_size.y);
+        // Sprites re-slice their tiles whenever they change.
+        if sprite.custom_size != Some(size) {
+            sprite.custom_size = Some(size);
+        }
+    }
+}
@@ -0,0 +1,310 @@
+//! Food and other items lying around the level.
+//!
+//! Food items become [`Food`], see [`Item::food_effects`].
+//! Everything else is collected just by walking into it, and is pulled
+//! towards the goose once it gets close.
+//! Defeated enemies scatter pickups from their [`LootTable`].
+
+use bevy::{
+    image::{ImageLoaderSettings, ImageSampler},
+    prelude::*,
+};
+use serde::Deserialize;
+
+use crate::{
+    AppSystems, PausableSystems,
+    asset_tracking::LoadResource,
+    audio::sound_effect,
+    demo::{
+        food::{BuffKind, Food, FoodEffect},
+        gun::Gun,
+        health::{Died, Health},
+        movement::MovementController,
+        player::Player,
+    },
+    screens::Screen,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.load_resource::<PickupAssets>();
+    app.init_resource::<Coins>();
+    app.add_systems(OnEnter(Screen::Gameplay), reset_coins);
+    app.add_systems(
+        Update,
+        (magnetise, collect)
+            .chain()
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems),
+    );
+    app.add_observer(make_food);
+    app.add_observer(drop_loot);
+}
+
+#[derive(Resource, Asset, Clone, Reflect)]
+#[reflect(Resource)]
+pub struct PickupAssets {
+    #[dependency]
+    hay: Handle<Image>,
+    #[dependency]
+    pistol: Handle<Image>,
+    #[dependency]
+    feather: Handle<Image>,
+    #[dependency]
+    ammo: Handle<Image>,
+    #[dependency]
+    coin: Handle<Image>,
+    #[dependency]
+    clover: Handle<Image>,
+    #[dependency]
+    collect_sound: Handle<AudioSource>,
+}
+
+impl FromWorld for PickupAssets {
+    fn from_world(world: &mut World) -> Self {
+        let assets = world.resource::<AssetServer>();
+        let pixel_art = |settings: &mut ImageLoaderSettings| {
+            settings.sampler = ImageSampler::nearest();
+        };
+        Self {
+            hay: assets.load_with_settings("images/hay.png", pixel_art),
+            pistol: assets.load_with_settings("images/pistol.png", pixel_art),
+            feather: assets.load_with_settings("images/feather.png", pixel_art),
+            ammo: assets.load_with_settings("images/ammo.png", pixel_art),
+            coin: assets.load_with_settings("images/coin.png", pixel_art),
+            clover: assets.load_with_settings("images/clover.png", pixel_art),
+            collect_sound: assets.load("audio/sound_effects/button_click.ogg"),
+        }
+    }
+}
+
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum Item {
+    Hay,
+    /// Eating it lets the goose shoot.
+    Pistol,
+    /// Restores some health.
+    Feather,
+    /// Refills the gun.
+    Ammo,
+    Coin,
+    /// A rare find that lifts some of the curse.
+    Clover,
+}
+
+impl Item {
+    /// What eating the item does. Empty for items that aren't food.
+    pub fn food_effects(self) -> &'static [FoodEffect] {
+        match self {
+            Self::Hay => &[
+                FoodEffect::Heal(10.0),
+                FoodEffect::Nourish(0.35),
+                FoodEffect::Buff {
+                    kind: BuffKind::Speed,
+                    multiplier: 1.25,
+                    secs: 6.0,
+                },
+            ],
+            Self::Pistol => &[
+                FoodEffect::GiveGun { ammo: 60 },
+                FoodEffect::Curse(1),
+                FoodEffect::Nourish(0.1),
+            ],
+            Self::Clover => &[
+                FoodEffect::Curse(-2),
+                FoodEffect::Nourish(0.2),
+                FoodEffect::RefillGlide,
+                FoodEffect::Buff {
+                    kind: BuffKind::Jump,
+                    multiplier: 1.3,
+                    secs: 8.0,
+                },
+            ],
+            Self::Feather | Self::Ammo | Self::Coin => &[],
+        }
+    }
+
+    /// Food has to be eaten, everything else is collected on touch.
+    pub fn is_food(self) -> bool {
+        !self.food_effects().is_empty()
+    }
+
+    pub fn image(self, pickup_assets: &PickupAssets) -> Handle<Image> {
+        match self {
+            Self::Hay => pickup_assets.hay.clone(),
+            Self::Pistol => pickup_assets.pistol.clone(),
+            Self::Feather => pickup_assets.feather.clone(),
+            Self::Ammo => pickup_assets.ammo.clone(),
+            Self::Coin => pickup_assets.coin.clone(),
+            Self::Clover => pickup_assets.clover.clone(),
+        }
+    }
+}
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct Pickup {
+    pub item: Item,
+}
+
+/// Coins collected during the current run.
+#[derive(Resource, Reflect, Debug, Default)]
+#[reflect(Resource)]
+pub struct Coins(pub u32);
+
+/// An item placed in the level.
+pub fn pickup(item: Item, location: Vec2, pickup_assets: &PickupAssets) -> impl Bundle {
+    (
+        Name::new(format!("{item:?}")),
+        Pickup { item },
+        Transform::from_translation(location.extend(3.0)).with_scale(Vec3::new(1.5, 1.5, 1.0)),
+        Sprite {
+            image: item.image(pickup_assets),
+            ..Default::default()
+        },
+    )
+}
+
+/// An item tossed into the air that bounces until it settles.
+fn dropped_pickup(item: Item, location: Vec2, pickup_assets: &PickupAssets) -> impl Bundle {
+    (
+        pickup(item, location, pickup_assets),
+        MovementController {
+            speed: 0.0,
+            velocity: Vec2::new(0.0, 900.0 + rand::random::<f32>() * 500.0),
+            knockback: (rand::random::<f32>() - 0.5) * 600.0,
+            bounciness: 0.45,
+            ..default()
+        },
+        DespawnOnExit(Screen::Gameplay),
+    )
+}
+
+/// A pickup flying towards the goose.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct Magnetised {
+    speed: f32,
+}
+
+impl Magnetised {
+    /// Non-food pickups closer than this start flying to the goose.
+    const RADIUS: f32 = 160.0;
+    const ACCELERATION: f32 = 2400.0;
+}
+
+/// One row of a [`LootTable`].
+#[derive(Reflect, Debug, Clone, Copy, Deserialize)]
+pub struct LootDrop {
+    pub item: Item,
+    /// Chance between 0 and 1 of each of the `count` rolls dropping the item.
+    pub chance: f32,
+    pub count: u32,
+}
+
+impl LootDrop {
+    pub fn new(item: Item, chance: f32, count: u32) -> Self {
+        Self {
+            item,
+            chance,
+            count,
+        }
+    }
+}
+
+/// Items dropped when this entity dies.
+#[derive(Component, Reflect, Debug, Clone, Default)]
+#[reflect(Component)]
+pub struct LootTable(pub Vec<LootDrop>);
+
+fn reset_coins(mut coins: ResMut<Coins>) {
+    coins.0 = 0;
+}
+
+fn drop_loot(
+    died: On<Died>,
+    mut commands: Commands,
+    pickup_assets: If<Res<PickupAssets>>,
+    loot_query: Query<(&Transform, &LootTable)>,
+) {
+    let Ok((transform, loot_table)) = loot_query.get(died.entity) else {
+        return;
+    };
+    let location = transform.translation.xy();
+    for drop in &loot_table.0 {
+        for _ in 0..drop.count {
+            if rand::random::<f32>() < drop.chance {
+                commands.spawn(dropped_pickup(drop.item, location, &pickup_assets));
+            }
+        }
+    }
+}
+
+/// Food items wait to be eaten instead of being collected.
+fn make_food(add: On<Add, Pickup>, mut commands: Commands, pickup_query: Query<&Pickup>) {
+    let Ok(pickup) = pickup_query.get(add.entity) else {
+        return;
+    };
+    if pickup.item.is_food() {
+        commands.entity(add.entity).insert(Food {
+            effects: pickup.item.food_effects(),
+        });
+    }
+}
+
+/// Pull nearby pickups towards the goose, ignoring gravity and platforms.
+fn magnetise(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut pickup_query: Query<
+        (Entity, &mut Transform, Option<&mut Magnetised>),
+        (With<Pickup>, Without<Food>),
+    >,
+    player_query: Query<&Transform, (With<Player>, Without<Pickup>)>,
+) {
+    let Ok(player_transform) = player_query.single() else {
+        return;
+    };
+    let player_position = player_transform.translation.xy();
+    for (entity, mut transform, magnetised) in &mut pickup_query {
+        let offset = player_position - transform.translation.xy();
+        match magnetised {
+            Some(mut magnetised) => {
+                magnetised.speed += Magnetised::ACCELERATION * time.delta_secs();
+                let step = (magnetised.speed * time.delta_secs()).min(offset.length());
+                transform.translation += (offset.normalize_or_zero() * step).extend(0.0);
+            }
+            None if offset.length() < Magnetised::RADIUS => {
+                commands
+                    .entity(entity)
+                    .remove::<MovementController>()
+                    .insert(Magnetised { speed: 200.0 });
+            }
+            None => {}
+        }
+    }
+}
+
+fn collect(
+    mut commands: Commands,
+    pickup_assets: If<Res<PickupAssets>>,
+    pickup_query: Query<(Entity, &Transform, &Pickup), Without<Food>>,
+    mut player_query: Query<(&Transform, &mut Health, &mut Gun), With<Player>>,
+    mut coins: ResMut<Coins>,
+) {
+    let Ok((player_transform, mut health, mut gun)) = player_query.single_mut() else {
+        return;
+    };
+    for (entity, transform, pickup) in &pickup_query {
+        if player_transform.translation.distance(transform.translation) >= 32.0 {
+            continue;
+        }
+        match pickup.item {
+            Item::Feather => health.current = (health.current + 25.0).min(health.max),
+            Item::Ammo => gun.ammo += 15,
+            Item::Coin => coins.0 += 1,
+            Item::Hay | Item::Pistol | Item::Clover => {}
+        }
+        commands.spawn(sound_effect(pickup_assets.collect_sound.clone()));
+        commands.entity(entity).despawn();
+    }
+}
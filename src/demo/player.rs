@@ -11,10 +11,16 @@ use crate::{
     audio::sound_effect,
     demo::{
         animation::MovementAnimation,
-        food::Food,
-        gun::{self, Gun},
-        movement::{FollowCamera, MovementController},
+        camera::FollowCamera,
+        checkpoint::ActiveCheckpoint,
+        cutscene::Cutscene,
+        food::{Buffs, Hunger},
+        gun::Gun,
+        health::{Died, Health},
+        movement::MovementController,
     },
+    input::{Action, ActionState},
+    settings::Settings,
 };
 
 
@@ -24,19 +30,23 @@ pub(super) fn plugin(app: &mut App) {
     // Record directional input as movement controls.
     app.add_systems(
         Update,
-        (record_player_directional_input, record_shooting_input, update_health_bar)
+        (
+            record_player_directional_input,
+            record_shooting_input,
+        )
+            .run_if(in_state(Cutscene(false)))
             .in_set(AppSystems::RecordInput)
             .in_set(PausableSystems),
     );
+    app.add_observer(respawn_or_end_game_on_death);
 }
 
 /// The player character.
 pub fn player(
     max_speed: f32,
+    location: Vec2,
     player_assets: &PlayerAssets,
     texture_atlas_layouts: &mut Assets<TextureAtlasLayout>,
-    meshes: &mut ResMut<Assets<Mesh>>,
-    materials: &mut ResMut<Assets<ColorMaterial>>,
 ) -> impl Bundle {
     // A texture atlas is a way to split a single image into a grid of related images.
     // You can learn more in this example: https://github.com/bevyengine/bevy/blob/latest/examples/2d/texture_atlas.rs
@@ -46,11 +56,10 @@ pub fn player(
 
     (
         Name::new("Player"),
-        Player {
-            has_gun: false,
-            health: 100.0,
-            max_health: 100.0,
-        },
+        Player { has_gun: false },
+        Health::new(100.0),
+        Buffs::default(),
+        Hunger::default(),
         Sprite::from_atlas_image(
             player_assets.goose.clone(),
             TextureAtlas {
@@ -58,7 +67,7 @@ pub fn player(
                 index: player_animation.get_atlas_index(),
             },
         ),
-        Transform::from_xyz(-250.0, -200.0, 10.0).with_scale(Vec2::splat(2.0).extend(1.0)),
+        Transform::from_translation(location.extend(10.0)).with_scale(Vec2::splat(2.0).extend(1.0)),
         MovementController {
             speed: max_speed,
             ..default()
@@ -68,24 +77,10 @@ pub fn player(
         Gun {
             shooting: false,
             can_shoot: true,
-            shooting_cooldown: Timer::from_seconds(0.8, TimerMode::Repeating),
+            shooting_cooldown: Timer::from_seconds(Gun::DEFAULT_COOLDOWN, TimerMode::Repeating),
             enabled: false,
+            ammo: 0,
         },
-        children![
-            (
-                Mesh2d(meshes.add(Rectangle::new(80.0, 6.0))),
-                MeshMaterial2d(materials.add(Color::srgb(1.0, 0.0, 0.0))),
-                Transform::from_xyz(0.0, 50.0, 4.0),
-            ), 
-            (
-                Mesh2d(meshes.add(Rectangle::new(80.0, 6.0))),
-                MeshMaterial2d(materials.add(Color::srgb(0.0, 1.0, 0.0))),
-                Transform::from_xyz(0.0, 50.0, 5.0),
-                HealthBar,
-            ), 
-            
-        ]
-
     )
 }
 
@@ -93,37 +88,40 @@ pub fn player(
 #[reflect(Component)]
 pub struct Player {
     pub has_gun: bool,
-    pub health: f32,
-    pub max_health: f32,
 }
 
 fn record_player_directional_input(
-    input: Res<ButtonInput<KeyCode>>,
+    actions: Res<ActionState>,
+    settings: Res<Settings>,
     mut controller_query: Query<&mut MovementController, With<Player>>,
+    // With toggle glide, whether a press in the air switched gliding on.
+    mut glide_toggled: Local<bool>,
 ) {
     // Collect directional input.
-    let mut intent = 0.0;
-    if input.pressed(KeyCode::KeyA) || input.pressed(KeyCode::ArrowLeft) {
-        intent -= 1.0;
-    }
-    if input.pressed(KeyCode::KeyD) || input.pressed(KeyCode::ArrowRight) {
-        intent += 1.0;
-    }
+    let intent = actions.horizontal();
 
     // Apply movement intent to controllers.
     for mut controller in &mut controller_query {
         // Jump
-        if input.pressed(KeyCode::Space) {
-            if controller.grounded {
-                controller.velocity.y = controller.jump_force * 3.0;
-                controller.grounded = false;
-            } else if controller.velocity.y < 0.0 && controller.jump_timer < controller.jump_time {
-                controller.gliding = true;
-            } else {
-                controller.gliding = false;
-            }
+        if actions.pressed(Action::Jump) && controller.grounded {
+            controller.velocity.y = controller.jump_force * controller.jump_multiplier * 3.0;
+            controller.grounded = false;
         } else {
-            controller.gliding = false;
+            // Glide
+            let glide = if settings.toggle_glide {
+                if controller.grounded {
+                    *glide_toggled = false;
+                } else if actions.just_pressed(Action::Glide) {
+                    *glide_toggled = !*glide_toggled;
+                }
+                *glide_toggled
+            } else {
+                actions.pressed(Action::Glide)
+            };
+            controller.gliding = glide
+                && !controller.grounded
+                && controller.velocity.y < 0.0
+                && controller.jump_timer < controller.jump_time;
         }
         // Movement
         controller.horizontal = intent;
@@ -139,18 +137,18 @@ fn record_player_directional_input(
 fn record_shooting_input(
     mut commands: Commands,
     player_assets: If<Res<PlayerAssets>>,
-    input: Res<ButtonInput<MouseButton>>,
+    actions: Res<ActionState>,
     mut gun_query: Query<&mut Gun>,
 ) {
     for mut gun in &mut gun_query {
         if gun.enabled {
             if gun.shooting_cooldown.duration().as_secs_f32() <= 0.3 {
-                gun.shooting = input.pressed(MouseButton::Left);
+                gun.shooting = actions.pressed(Action::Shoot);
             } else {
-                gun.shooting = input.just_pressed(MouseButton::Left);
+                gun.shooting = actions.just_pressed(Action::Shoot);
             }
         }
-        if input.just_pressed(MouseButton::Left) {
+        if actions.just_pressed(Action::Shoot) {
             commands.spawn(sound_effect(player_assets.honk.first().unwrap().clone()));
         }
     }
@@ -200,19 +198,23 @@ impl FromWorld for PlayerAssets {
     }
 }
 
-#[derive(Component, Reflect, Debug)]
-#[reflect(Component)]
-struct HealthBar;
-
-fn update_health_bar(
-    player_query: Query<&Player>,
-    mut health_bar_query: Query<&mut Transform, With<HealthBar>>,
+/// Respawns the goose at the last checkpoint it reached, if any.
+fn respawn_or_end_game_on_death(
+    died: On<Died>,
+    active_checkpoint: Res<ActiveCheckpoint>,
+    mut player_query: Query<(&mut Transform, &mut Health, &mut MovementController), With<Player>>,
+    mut app_exit: MessageWriter<AppExit>,
 ) {
-    for player in player_query {
-        for mut health_bar_transform in health_bar_query.iter_mut() {
-            health_bar_transform.scale.x = (player.health / player.max_health).max(0.0);
-            health_bar_transform.translation.x = (player.health / player.max_health) * 40.0 - 40.0;
-
-        }
-    } 
-}
\ No newline at end of file
+    let Ok((mut transform, mut health, mut controller)) = player_query.get_mut(died.entity) else {
+        return;
+    };
+    let Some(spawn_point) = active_checkpoint.0 else {
+        app_exit.write(AppExit::Success);
+        return;
+    };
+    // Drop in from above the foot of the flag, so the goose lands on the ground.
+    transform.translation = (spawn_point + Vec2::Y * 64.0).extend(transform.translation.z);
+    health.current = health.max;
+    controller.velocity = Vec2::ZERO;
+    controller.knockback = 0.0;
+}
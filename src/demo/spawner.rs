@@ -0,0 +1,320 @@
+//! Enemy spawners and arena encounters.
+//!
+//! A [`Spawner`] keeps a trickle of enemies coming while the player is near.
+//! An [`Arena`] locks the player and the camera in until every one of its
+//! [`Wave`]s has been defeated.
+
+use bevy::prelude::*;
+
+use crate::{
+    AppSystems, PausableSystems,
+    demo::{
+        aabb::AABB,
+        camera::CameraLock,
+        enemy::Enemies,
+        platform::Platform,
+        player::Player,
+    },
+    screens::Screen,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        (
+            tick_spawners.in_set(AppSystems::TickTimers),
+            (run_spawners, run_arenas).in_set(AppSystems::Update),
+        )
+            .in_set(PausableSystems),
+    );
+}
+
+/// The spawner or arena an enemy came from.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+#[relationship(relationship_target = Spawned)]
+pub struct SpawnedBy(pub Entity);
+
+/// Enemies still alive that were spawned by this entity.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+#[relationship_target(relationship = SpawnedBy)]
+pub struct Spawned(Vec<Entity>);
+
+impl Spawned {
+    pub fn count(&self) -> usize {
+        self.0.len()
+    }
+}
+
+/// One row of a spawn table.
+#[derive(Reflect, Debug, Clone)]
+pub struct SpawnEntry {
+    /// Id in the [`EnemyRegistry`](super::enemy::EnemyRegistry).
+    pub enemy: String,
+    /// Relative chance of this entry being picked.
+    pub weight: f32,
+}
+
+impl SpawnEntry {
+    pub fn new(enemy: impl Into<String>, weight: f32) -> Self {
+        Self {
+            enemy: enemy.into(),
+            weight,
+        }
+    }
+}
+
+/// Pick an enemy id from a weighted table.
+fn pick(table: &[SpawnEntry]) -> Option<&str> {
+    let total: f32 = table.iter().map(|entry| entry.weight).sum();
+    let mut roll = rand::random::<f32>() * total;
+    for entry in table {
+        if roll < entry.weight {
+            return Some(&entry.enemy);
+        }
+        roll -= entry.weight;
+    }
+    table.last().map(|entry| entry.enemy.as_str())
+}
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct Spawner {
+    pub table: Vec<SpawnEntry>,
+    /// No more enemies are spawned while this many are alive.
+    pub max_alive: usize,
+    pub cooldown: Timer,
+    /// The spawner only works while the player is closer than this.
+    pub activation_radius: f32,
+    /// How many more enemies may be spawned, or `None` for endless.
+    pub remaining: Option<usize>,
+}
+
+pub fn spawner(
+    location: Vec2,
+    table: Vec<SpawnEntry>,
+    max_alive: usize,
+    cooldown_secs: f32,
+    remaining: Option<usize>,
+) -> impl Bundle {
+    (
+        Name::new("Spawner"),
+        Spawner {
+            table,
+            max_alive,
+            cooldown: Timer::from_seconds(cooldown_secs, TimerMode::Repeating),
+            activation_radius: 900.0,
+            remaining,
+        },
+        Transform::from_translation(location.extend(4.0)),
+    )
+}
+
+/// An enemy spawned by `source` that is cleaned up with the level.
+pub fn spawned_enemy(
+    source: Entity,
+    enemies: &Enemies,
+    id: &str,
+    location: Vec2,
+) -> Result<impl Bundle + use<>> {
+    // Flying enemies cruise well above the spawn point.
+    let flying = enemies
+        .definition(id)
+        .is_some_and(|definition| definition.is_flying());
+    let height = if flying { 250.0 } else { 0.0 };
+    Ok((
+        enemies.enemy(id, (location + Vec2::Y * height).extend(4.0))?,
+        SpawnedBy(source),
+        DespawnOnExit(Screen::Gameplay),
+    ))
+}
+
+fn tick_spawners(time: Res<Time>, mut spawner_query: Query<&mut Spawner>) {
+    for mut spawner in &mut spawner_query {
+        spawner.cooldown.tick(time.delta());
+    }
+}
+
+fn run_spawners(
+    mut commands: Commands,
+    mut spawner_query: Query<(Entity, &mut Spawner, &Transform, Option<&Spawned>)>,
+    player_query: Query<&Transform, With<Player>>,
+    enemies: Enemies,
+) {
+    let Ok(player_transform) = player_query.single() else {
+        return;
+    };
+    for (entity, mut spawner, transform, spawned) in &mut spawner_query {
+        let location = transform.translation.xy();
+        if !spawner.cooldown.just_finished()
+            || spawner.remaining == Some(0)
+            || spawned.map_or(0, Spawned::count) >= spawner.max_alive
+            || location.distance(player_transform.translation.xy()) > spawner.activation_radius
+        {
+            continue;
+        }
+        let Some(id) = pick(&spawner.table) else {
+            continue;
+        };
+        let jitter = Vec2::X * (rand::random::<f32>() - 0.5) * 100.0;
+        match spawned_enemy(entity, &enemies, id, location + jitter) {
+            Ok(enemy) => {
+                commands.spawn(enemy);
+            }
+            Err(error) => {
+                warn!("Spawner can't spawn `{id}`: {error}");
+                continue;
+            }
+        }
+        if let Some(remaining) = &mut spawner.remaining {
+            *remaining -= 1;
+        }
+    }
+}
+
+/// A group of enemies spawned together.
+#[derive(Reflect, Debug, Clone)]
+pub struct Wave {
+    /// Enemy ids in the [`EnemyRegistry`](super::enemy::EnemyRegistry) and how
+    /// many of each to spawn.
+    pub enemies: Vec<(String, usize)>,
+}
+
+impl Wave {
+    pub fn new(enemies: &[(&str, usize)]) -> Self {
+        Self {
+            enemies: enemies
+                .iter()
+                .map(|(id, count)| (id.to_string(), *count))
+                .collect(),
+        }
+    }
+}
+
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArenaState {
+    /// Waiting for the player to walk in.
+    Dormant,
+    /// Walls are up and waves are being fought.
+    Active,
+    Cleared,
+}
+
+/// A section of the level between `left` and `right` that is walled off
+/// until all waves are cleared.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct Arena {
+    pub left: f32,
+    pub right: f32,
+    /// Height of the arena floor, where ground enemies are spawned.
+    pub floor_y: f32,
+    pub waves: Vec<Wave>,
+    pub state: ArenaState,
+    /// Index of the next wave to spawn.
+    next_wave: usize,
+    /// Breather between one wave dying and the next appearing.
+    wave_delay: Timer,
+}
+
+pub fn arena(left: f32, right: f32, floor_y: f32, waves: Vec<Wave>) -> impl Bundle {
+    (
+        Name::new("Arena"),
+        Arena {
+            left,
+            right,
+            floor_y,
+            waves,
+            state: ArenaState::Dormant,
+            next_wave: 0,
+            wave_delay: Timer::from_seconds(1.5, TimerMode::Once),
+        },
+        // Walls are children, so keep the arena at the origin to place them in world space.
+        Transform::default(),
+        Visibility::default(),
+    )
+}
+
+/// A wall too tall to glide over, standing on `floor_y`.
+pub fn arena_wall(x: f32, floor_y: f32) -> impl Bundle {
+    let center = Vec2::new(x, floor_y + 512.0);
+    let half_size = Vec2::new(16.0, 640.0);
+    (
+        Name::new("Arena Wall"),
+        Platform,
+        AABB { center, half_size },
+        Sprite::from_color(Color::srgba(0.45, 0.3, 0.15, 0.8), half_size * 2.0),
+        Transform::from_translation(center.extend(9.0)),
+    )
+}
+
+fn run_arenas(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut arena_query: Query<(Entity, &mut Arena, Option<&Spawned>)>,
+    player_query: Query<&Transform, With<Player>>,
+    enemies: Enemies,
+    mut camera_lock: ResMut<CameraLock>,
+) {
+    let Ok(player_transform) = player_query.single() else {
+        return;
+    };
+    let player_x = player_transform.translation.x;
+    for (entity, mut arena, spawned) in &mut arena_query {
+        match arena.state {
+            ArenaState::Dormant => {
+                // Only close the doors once the player is fully inside.
+                if player_x > arena.left + 128.0 && player_x < arena.right - 128.0 {
+                    arena.state = ArenaState::Active;
+                    arena.wave_delay.reset();
+                    camera_lock.region = Some((arena.left, arena.right));
+                    let walls = [
+                        arena_wall(arena.left, arena.floor_y),
+                        arena_wall(arena.right, arena.floor_y),
+                    ];
+                    commands.entity(entity).with_children(|parent| {
+                        for wall in walls {
+                            parent.spawn(wall);
+                        }
+                    });
+                }
+            }
+            ArenaState::Active => {
+                if spawned.is_some_and(|spawned| spawned.count() > 0) {
+                    continue;
+                }
+                arena.wave_delay.tick(time.delta());
+                if !arena.wave_delay.is_finished() {
+                    continue;
+                }
+                arena.wave_delay.reset();
+
+                let Some(wave) = arena.waves.get(arena.next_wave).cloned() else {
+                    arena.state = ArenaState::Cleared;
+                    camera_lock.region = None;
+                    commands.entity(entity).despawn_related::<Children>();
+                    continue;
+                };
+                arena.next_wave += 1;
+                for (id, count) in &wave.enemies {
+                    for _ in 0..*count {
+                        let x = arena.left
+                            + 128.0
+                            + rand::random::<f32>() * (arena.right - arena.left - 256.0);
+                        match spawned_enemy(entity, &enemies, id, Vec2::new(x, arena.floor_y)) {
+                            Ok(enemy) => {
+                                commands.spawn(enemy);
+                            }
+                            Err(error) => {
+                                warn!("Arena wave can't spawn `{id}`: {error}");
+                                break;
+                            }
+                        }
+                    }
+                }
+            }
+            ArenaState::Cleared => {}
+        }
+    }
+}
@@ -0,0 +1,262 @@
+//! Levels laid out in the [Tiled](https://www.mapeditor.org) editor.
+//!
+//! Maps saved in Tiled's JSON format (`assets/levels/*.tmj`) load as a
+//! [`LevelMap`]. Every tile layer becomes a [`TileLayer`] named after it and
+//! drawn with the tileset its tiles come from. Which tile was painted doesn't
+//! matter, as tiles are picked by autotiling, and a layer with the boolean
+//! property `solid` unset is only decoration. Objects in object layers become
+//! [`LevelObject`]s, spawned by the level according to their class.
+//!
+//! Custom map properties `origin_x` and `origin_y` place the bottom left corner
+//! of the map in the world. Tiled pixels are world units, with y pointing up.
+//! The map's size is the area the camera may show.
+
+use std::collections::HashMap;
+
+use bevy::{
+    asset::{AssetLoader, LoadContext, io::Reader},
+    prelude::*,
+};
+use serde::{Deserialize, de::DeserializeOwned};
+use serde_json::Value;
+
+use crate::demo::tilemap::{TileLayer, tileset_settings};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_asset::<LevelMap>();
+    app.register_asset_loader(LevelMapLoader);
+}
+
+/// The contents of a Tiled map.
+#[derive(Asset, TypePath, Debug)]
+pub struct LevelMap {
+    /// Area covered by the map in world space.
+    pub bounds: Rect,
+    pub tile_layers: Vec<TileLayer>,
+    pub objects: Vec<LevelObject>,
+}
+
+/// An object placed in a Tiled object layer.
+#[derive(Debug, Clone)]
+pub struct LevelObject {
+    /// The object's class in Tiled, deciding what it spawns as.
+    pub class: String,
+    pub name: String,
+    /// Area covered by the object in world space. Points have no size.
+    pub rect: Rect,
+    properties: HashMap<String, Value>,
+}
+
+impl LevelObject {
+    /// Center of the object in world space.
+    pub fn position(&self) -> Vec2 {
+        self.rect.center()
+    }
+
+    /// A custom property, or `None` if it is missing or of the wrong type.
+    pub fn property<T: DeserializeOwned>(&self, name: &str) -> Option<T> {
+        self.properties
+            .get(name)
+            .and_then(|value| T::deserialize(value).ok())
+    }
+}
+
+/// The parts of Tiled's JSON map format levels use.
+#[derive(Deserialize)]
+struct TmjMap {
+    width: u32,
+    height: u32,
+    tilewidth: u32,
+    tileheight: u32,
+    #[serde(default)]
+    properties: Vec<TmjProperty>,
+    layers: Vec<TmjLayer>,
+    #[serde(default)]
+    tilesets: Vec<TmjTileset>,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum TmjLayer {
+    TileLayer {
+        name: String,
+        width: u32,
+        height: u32,
+        data: Vec<u32>,
+        #[serde(default)]
+        properties: Vec<TmjProperty>,
+    },
+    ObjectGroup {
+        objects: Vec<TmjObject>,
+    },
+    /// Image and group layers aren't used by levels.
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Deserialize)]
+struct TmjTileset {
+    firstgid: u32,
+    /// Only embedded tilesets made from a single image are supported.
+    image: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct TmjObject {
+    /// Called `class` by Tiled 1.9, and `type` before and after it.
+    #[serde(default, rename = "type", alias = "class")]
+    class: String,
+    #[serde(default)]
+    name: String,
+    x: f32,
+    y: f32,
+    #[serde(default)]
+    width: f32,
+    #[serde(default)]
+    height: f32,
+    #[serde(default)]
+    properties: Vec<TmjProperty>,
+}
+
+#[derive(Deserialize)]
+struct TmjProperty {
+    name: String,
+    value: Value,
+}
+
+fn properties(properties: Vec<TmjProperty>) -> HashMap<String, Value> {
+    properties
+        .into_iter()
+        .map(|property| (property.name, property.value))
+        .collect()
+}
+
+#[derive(TypePath, Default)]
+struct LevelMapLoader;
+
+impl AssetLoader for LevelMapLoader {
+    type Asset = LevelMap;
+    type Settings = ();
+    type Error = BevyError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let map: TmjMap = serde_json::from_slice(&bytes)?;
+
+        let map_properties = properties(map.properties);
+        let origin_property = |name: &str| {
+            map_properties
+                .get(name)
+                .and_then(Value::as_f64)
+                .unwrap_or_default() as f32
+        };
+        let origin = Vec2::new(origin_property("origin_x"), origin_property("origin_y"));
+        let map_size = UVec2::new(map.width * map.tilewidth, map.height * map.tileheight).as_vec2();
+        let map_height = map_size.y;
+        // Tiled measures y down from the top of the map.
+        let to_world = |x: f32, y: f32| origin + Vec2::new(x, map_height - y);
+
+        let mut tilesets = Vec::new();
+        for tileset in &map.tilesets {
+            let Some(image) = &tileset.image else {
+                return Err(BevyError::from(format!(
+                    "tileset at gid {} of {} must be embedded in the map and use a single image",
+                    tileset.firstgid,
+                    load_context.path(),
+                )));
+            };
+            let path = load_context.path().resolve_embed(image)?;
+            let handle = load_context
+                .loader()
+                .with_settings(tileset_settings)
+                .load(path);
+            tilesets.push((tileset.firstgid, handle));
+        }
+        // Searched from the highest first gid down to find a tile's tileset.
+        tilesets.sort_by_key(|&(firstgid, _)| std::cmp::Reverse(firstgid));
+
+        let mut tile_layers = Vec::new();
+        let mut objects = Vec::new();
+        for layer in map.layers {
+            match layer {
+                TmjLayer::TileLayer {
+                    name,
+                    width,
+                    height,
+                    data,
+                    properties: layer_properties,
+                } => {
+                    // Flip flags live in the top bits of a gid.
+                    let Some(&gid) = data.iter().find(|&&gid| gid != 0) else {
+                        continue;
+                    };
+                    let gid = gid & 0x0fff_ffff;
+                    let Some((_, tileset)) =
+                        tilesets.iter().find(|&&(firstgid, _)| firstgid <= gid)
+                    else {
+                        return Err(BevyError::from(format!(
+                            "layer `{name}` of {} uses a missing tileset",
+                            load_context.path(),
+                        )));
+                    };
+                    if map.tilewidth != map.tileheight {
+                        return Err(BevyError::from(format!(
+                            "tiles of {} must be square",
+                            load_context.path(),
+                        )));
+                    }
+                    let mut tile_layer = TileLayer::new(
+                        &name,
+                        tileset.clone(),
+                        to_world(0.0, (height * map.tileheight) as f32),
+                        map.tilewidth,
+                        UVec2::new(width, height),
+                    );
+                    tile_layer.solid = properties(layer_properties)
+                        .get("solid")
+                        .and_then(Value::as_bool)
+                        .unwrap_or(true);
+                    for (index, &gid) in data.iter().enumerate() {
+                        let row = index as u32 / width;
+                        let column = index as u32 % width;
+                        tile_layer.set(UVec2::new(column, height - 1 - row), gid != 0);
+                    }
+                    tile_layers.push(tile_layer);
+                }
+                TmjLayer::ObjectGroup {
+                    objects: layer_objects,
+                } => {
+                    objects.extend(layer_objects.into_iter().map(|object| {
+                        let corner = to_world(object.x, object.y);
+                        LevelObject {
+                            class: object.class,
+                            name: object.name,
+                            rect: Rect::from_corners(
+                                corner,
+                                corner + Vec2::new(object.width, -object.height),
+                            ),
+                            properties: properties(object.properties),
+                        }
+                    }));
+                }
+                TmjLayer::Other => {}
+            }
+        }
+
+        Ok(LevelMap {
+            bounds: Rect::from_corners(origin, origin + map_size),
+            tile_layers,
+            objects,
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["tmj"]
+    }
+}
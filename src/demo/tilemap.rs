@@ -0,0 +1,204 @@
+//! Level geometry built from tiles.
+//!
+//! A [`TileLayer`] is a grid of filled and empty cells drawn with a tileset:
+//! an image of [`AUTOTILE_VARIANTS`] tiles stacked vertically, one for every
+//! combination of filled neighbours, so edges and corners pick the right tile
+//! by themselves. Layers are rendered in [`TilemapChunk`]s, letting long levels
+//! be culled a chunk at a time, and solid layers collide through [`Platform`]s
+//! made by merging their filled cells into as few boxes as possible.
+
+use bevy::{
+    ecs::spawn::SpawnWith,
+    image::{ImageArrayLayout, ImageLoaderSettings, ImageSampler},
+    math::URect,
+    prelude::*,
+    sprite_render::{AlphaMode2d, TileData, TilemapChunk, TilemapChunkTileData},
+};
+
+use crate::demo::{aabb::AABB, platform::Platform};
+
+/// Number of tiles in a tileset, one for each combination of the four neighbours.
+pub const AUTOTILE_VARIANTS: u32 = 16;
+
+/// Size of a rendered chunk in tiles.
+const CHUNK_SIZE: UVec2 = UVec2::new(32, 16);
+
+/// Loader settings for tileset images, splitting them into their tiles.
+pub fn tileset_settings(settings: &mut ImageLoaderSettings) {
+    settings.sampler = ImageSampler::nearest();
+    settings.array_layout = Some(ImageArrayLayout::RowCount {
+        rows: AUTOTILE_VARIANTS,
+    });
+}
+
+/// A grid of tiles drawn with one tileset, with cell (0, 0) at the bottom left.
+#[derive(Debug, Clone)]
+pub struct TileLayer {
+    /// Identifies the layer to [`CurseTarget::Tiles`](super::curse::CurseTarget::Tiles).
+    pub name: String,
+    pub tileset: Handle<Image>,
+    /// World position of the bottom left corner of cell (0, 0).
+    pub origin: Vec2,
+    /// Size of a tile in world units.
+    pub tile_size: u32,
+    /// Whether filled cells block movement, bullets and sight.
+    pub solid: bool,
+    size: UVec2,
+    cells: Vec<bool>,
+}
+
+impl TileLayer {
+    /// An empty, solid layer of `size` cells.
+    pub fn new(
+        name: &str,
+        tileset: Handle<Image>,
+        origin: Vec2,
+        tile_size: u32,
+        size: UVec2,
+    ) -> Self {
+        Self {
+            name: name.to_string(),
+            tileset,
+            origin,
+            tile_size,
+            solid: true,
+            size,
+            cells: vec![false; size.element_product() as usize],
+        }
+    }
+
+    pub fn set(&mut self, cell: UVec2, filled: bool) {
+        if cell.x < self.size.x && cell.y < self.size.y {
+            let index = self.index(cell);
+            self.cells[index] = filled;
+        }
+    }
+
+    /// Whether a cell is filled. Cells outside the layer are empty.
+    pub fn is_filled(&self, cell: IVec2) -> bool {
+        cell.cmpge(IVec2::ZERO).all()
+            && cell.cmplt(self.size.as_ivec2()).all()
+            && self.cells[self.index(cell.as_uvec2())]
+    }
+
+    fn index(&self, cell: UVec2) -> usize {
+        (cell.y * self.size.x + cell.x) as usize
+    }
+
+    /// The tile of a filled cell, from which of its neighbours are filled.
+    fn autotile_index(&self, cell: IVec2) -> u16 {
+        [IVec2::Y, IVec2::X, IVec2::NEG_Y, IVec2::NEG_X]
+            .into_iter()
+            .enumerate()
+            .filter(|&(_, direction)| self.is_filled(cell + direction))
+            .map(|(bit, _)| 1 << bit)
+            .sum()
+    }
+
+    /// Boxes covering exactly the filled cells, in cells. Each box grows as far
+    /// right and then as far up as it can, which is cheap and gives few boxes
+    /// for the mostly rectangular shapes of level geometry.
+    fn merged_rects(&self) -> Vec<URect> {
+        let mut covered = vec![false; self.cells.len()];
+        let free = |covered: &[bool], x: u32, y: u32| {
+            let index = self.index(UVec2::new(x, y));
+            self.cells[index] && !covered[index]
+        };
+        let mut rects = Vec::new();
+        for y in 0..self.size.y {
+            for x in 0..self.size.x {
+                if !free(&covered, x, y) {
+                    continue;
+                }
+                let mut max = UVec2::new(x + 1, y + 1);
+                while max.x < self.size.x && free(&covered, max.x, y) {
+                    max.x += 1;
+                }
+                while max.y < self.size.y && (x..max.x).all(|x| free(&covered, x, max.y)) {
+                    max.y += 1;
+                }
+                for covered_y in y..max.y {
+                    for covered_x in x..max.x {
+                        covered[self.index(UVec2::new(covered_x, covered_y))] = true;
+                    }
+                }
+                rects.push(URect::from_corners(UVec2::new(x, y), max));
+            }
+        }
+        rects
+    }
+
+    /// World space collider of a box of cells.
+    fn collider(&self, rect: URect) -> AABB {
+        let tile_size = self.tile_size as f32;
+        AABB {
+            center: self.origin + rect.as_rect().center() * tile_size,
+            half_size: rect.as_rect().half_size() * tile_size,
+        }
+    }
+
+    /// Tiles of the chunk starting at cell `min`, or `None` if it is empty.
+    fn chunk_tiles(&self, min: UVec2) -> Option<Vec<Option<TileData>>> {
+        let mut empty = true;
+        let tiles = (0..CHUNK_SIZE.y)
+            .flat_map(|y| (0..CHUNK_SIZE.x).map(move |x| (min + UVec2::new(x, y)).as_ivec2()))
+            .map(|cell| {
+                self.is_filled(cell).then(|| {
+                    empty = false;
+                    TileData::from_tileset_index(self.autotile_index(cell))
+                })
+            })
+            .collect();
+        (!empty).then_some(tiles)
+    }
+}
+
+/// A rendered chunk of a [`TileLayer`].
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct TileChunk {
+    pub layer: String,
+}
+
+/// A tile layer's chunks and colliders, drawn at depth `z`.
+pub fn tile_layer(layer: TileLayer, z: f32) -> impl Bundle {
+    (
+        Name::new(format!("Tile Layer {}", layer.name)),
+        // Colliders are in world space, so keep the layer at the origin.
+        Transform::default(),
+        Visibility::default(),
+        Children::spawn(SpawnWith(move |parent: &mut ChildSpawner| {
+            let tile_size = layer.tile_size as f32;
+            let chunk_world_size = CHUNK_SIZE.as_vec2() * tile_size;
+            let chunks = (layer.size + CHUNK_SIZE - UVec2::ONE) / CHUNK_SIZE;
+            for chunk_y in 0..chunks.y {
+                for chunk_x in 0..chunks.x {
+                    let min = UVec2::new(chunk_x, chunk_y) * CHUNK_SIZE;
+                    let Some(tiles) = layer.chunk_tiles(min) else {
+                        continue;
+                    };
+                    let center = layer.origin + min.as_vec2() * tile_size + chunk_world_size / 2.0;
+                    parent.spawn((
+                        Name::new("Tile Chunk"),
+                        TileChunk {
+                            layer: layer.name.clone(),
+                        },
+                        TilemapChunk {
+                            chunk_size: CHUNK_SIZE,
+                            tile_display_size: UVec2::splat(layer.tile_size),
+                            tileset: layer.tileset.clone(),
+                            alpha_mode: AlphaMode2d::Blend,
+                        },
+                        TilemapChunkTileData(tiles),
+                        Transform::from_translation(center.extend(z)),
+                    ));
+                }
+            }
+            if layer.solid {
+                for rect in layer.merged_rects() {
+                    parent.spawn((Name::new("Tile Collider"), Platform, layer.collider(rect)));
+                }
+            }
+        })),
+    )
+}
@@ -0,0 +1,350 @@
+//! Named input actions and the keys and buttons bound to them.
+//!
+//! Gameplay code reads [`ActionState`] instead of raw [`ButtonInput`], so the
+//! player can rebind controls in the settings menu. Keyboard, mouse and
+//! gamepad buttons can all be bound, and the left stick of any gamepad moves
+//! the goose as well. On-screen controls feed actions through
+//! [`VirtualInput`]. Bindings are saved with
+//! [`persistence`](crate::persistence) whenever they change.
+
+use std::collections::{HashMap, HashSet};
+
+use bevy::{input::InputSystems, prelude::*};
+use serde::{Deserialize, Serialize};
+
+use crate::persistence;
+
+pub(super) fn plugin(app: &mut App) {
+    app.insert_resource(InputBindings::load());
+    app.init_resource::<ActionState>();
+    app.init_resource::<VirtualInput>();
+    app.init_resource::<Rebinding>();
+    app.add_systems(
+        PreUpdate,
+        (capture_rebinding, update_action_state)
+            .chain()
+            .in_set(ActionSystems)
+            .after(InputSystems),
+    );
+}
+
+/// Reads input and updates the [`ActionState`] in [`PreUpdate`].
+#[derive(SystemSet, Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct ActionSystems;
+
+/// Something the player can do, independent of how it is triggered.
+#[derive(Reflect, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    MoveLeft,
+    MoveRight,
+    Jump,
+    Glide,
+    /// Honk, or shoot once the goose has a gun.
+    Shoot,
+    Eat,
+    Pause,
+}
+
+impl Action {
+    pub const ALL: [Self; 7] = [
+        Self::MoveLeft,
+        Self::MoveRight,
+        Self::Jump,
+        Self::Glide,
+        Self::Shoot,
+        Self::Eat,
+        Self::Pause,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::MoveLeft => "Move Left",
+            Self::MoveRight => "Move Right",
+            Self::Jump => "Jump",
+            Self::Glide => "Glide",
+            Self::Shoot => "Honk / Shoot",
+            Self::Eat => "Eat",
+            Self::Pause => "Pause",
+        }
+    }
+
+    fn default_bindings(self) -> Vec<Binding> {
+        match self {
+            Self::MoveLeft => vec![
+                Binding::Key(KeyCode::KeyA),
+                Binding::Key(KeyCode::ArrowLeft),
+                Binding::Gamepad(GamepadButton::DPadLeft),
+            ],
+            Self::MoveRight => vec![
+                Binding::Key(KeyCode::KeyD),
+                Binding::Key(KeyCode::ArrowRight),
+                Binding::Gamepad(GamepadButton::DPadRight),
+            ],
+            Self::Jump | Self::Glide => vec![
+                Binding::Key(KeyCode::Space),
+                Binding::Gamepad(GamepadButton::South),
+            ],
+            Self::Shoot => vec![
+                Binding::Mouse(MouseButton::Left),
+                Binding::Gamepad(GamepadButton::West),
+            ],
+            Self::Eat => vec![
+                Binding::Key(KeyCode::KeyE),
+                Binding::Gamepad(GamepadButton::North),
+            ],
+            Self::Pause => vec![
+                Binding::Key(KeyCode::Escape),
+                Binding::Key(KeyCode::KeyP),
+                Binding::Gamepad(GamepadButton::Start),
+            ],
+        }
+    }
+}
+
+#[derive(Reflect, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Binding {
+    Key(KeyCode),
+    Mouse(MouseButton),
+    Gamepad(GamepadButton),
+}
+
+impl Binding {
+    /// Keyboard and mouse count as one device, gamepads as the other.
+    fn is_gamepad(self) -> bool {
+        matches!(self, Self::Gamepad(_))
+    }
+
+    /// Short name for showing the binding to the player.
+    pub fn label(self) -> String {
+        match self {
+            Self::Key(key) => {
+                let name = format!("{key:?}");
+                ["Key", "Digit", "Arrow"]
+                    .iter()
+                    .find_map(|prefix| name.strip_prefix(prefix))
+                    .filter(|rest| !rest.is_empty())
+                    .map_or(name.clone(), str::to_string)
+            }
+            Self::Mouse(button) => format!("{button:?} Mouse"),
+            // Named after the common Xbox layout.
+            Self::Gamepad(button) => match button {
+                GamepadButton::South => "(A)".to_string(),
+                GamepadButton::East => "(B)".to_string(),
+                GamepadButton::West => "(X)".to_string(),
+                GamepadButton::North => "(Y)".to_string(),
+                GamepadButton::LeftTrigger => "LB".to_string(),
+                GamepadButton::RightTrigger => "RB".to_string(),
+                GamepadButton::LeftTrigger2 => "LT".to_string(),
+                GamepadButton::RightTrigger2 => "RT".to_string(),
+                GamepadButton::LeftThumb => "LS".to_string(),
+                GamepadButton::RightThumb => "RS".to_string(),
+                GamepadButton::DPadUp => "D-Pad Up".to_string(),
+                GamepadButton::DPadDown => "D-Pad Down".to_string(),
+                GamepadButton::DPadLeft => "D-Pad Left".to_string(),
+                GamepadButton::DPadRight => "D-Pad Right".to_string(),
+                button => format!("{button:?}"),
+            },
+        }
+    }
+}
+
+/// The bindings for every [`Action`].
+#[derive(Resource, Reflect, Serialize, Deserialize, Debug, Clone)]
+#[reflect(Resource)]
+pub struct InputBindings(HashMap<Action, Vec<Binding>>);
+
+impl Default for InputBindings {
+    fn default() -> Self {
+        Self(
+            Action::ALL
+                .into_iter()
+                .map(|action| (action, action.default_bindings()))
+                .collect(),
+        )
+    }
+}
+
+impl InputBindings {
+    const SAVE_NAME: &str = "bindings";
+
+    /// Saved bindings, with defaults for any actions that weren't saved.
+    fn load() -> Self {
+        let mut bindings = Self::default();
+        if let Some(saved) = persistence::load::<Self>(Self::SAVE_NAME) {
+            bindings.0.extend(saved.0);
+        }
+        bindings
+    }
+
+    pub fn save(&self) {
+        persistence::save(Self::SAVE_NAME, self);
+    }
+
+    pub fn get(&self, action: Action) -> &[Binding] {
+        self.0.get(&action).map_or(&[], Vec::as_slice)
+    }
+
+    /// Replace the action's bindings on the same kind of device as `binding`,
+    /// keeping the rest, so rebinding a key doesn't unbind the gamepad.
+    pub fn rebind(&mut self, action: Action, binding: Binding) {
+        let bindings = self.0.entry(action).or_default();
+        bindings.retain(|other| other.is_gamepad() != binding.is_gamepad());
+        bindings.push(binding);
+    }
+
+    /// All bindings of an action, e.g. "A / Left".
+    pub fn label(&self, action: Action) -> String {
+        let labels: Vec<_> = self
+            .get(action)
+            .iter()
+            .map(|binding| binding.label())
+            .collect();
+        if labels.is_empty() {
+            "Unbound".to_string()
+        } else {
+            labels.join(" / ")
+        }
+    }
+}
+
+/// Which actions are held this frame.
+#[derive(Resource, Reflect, Debug, Default)]
+#[reflect(Resource)]
+pub struct ActionState {
+    pressed: HashSet<Action>,
+    just_pressed: HashSet<Action>,
+    /// Horizontal position of the left stick or on-screen joystick, with the
+    /// deadzone removed.
+    stick: f32,
+}
+
+impl ActionState {
+    pub fn pressed(&self, action: Action) -> bool {
+        self.pressed.contains(&action)
+    }
+
+    pub fn just_pressed(&self, action: Action) -> bool {
+        self.just_pressed.contains(&action)
+    }
+
+    /// Stick positions closer to the center than this are ignored.
+    const STICK_DEADZONE: f32 = 0.2;
+
+    /// -1 for [`Action::MoveLeft`], 1 for [`Action::MoveRight`], 0 for both or neither.
+    ///
+    /// Without either action held, this follows the left stick instead.
+    pub fn horizontal(&self) -> f32 {
+        let mut intent = 0.0;
+        if self.pressed(Action::MoveLeft) {
+            intent -= 1.0;
+        }
+        if self.pressed(Action::MoveRight) {
+            intent += 1.0;
+        }
+        if intent == 0.0 { self.stick } else { intent }
+    }
+}
+
+/// Actions held by on-screen controls rather than a physical device.
+///
+/// Written before [`ActionSystems`] and merged into the [`ActionState`].
+#[derive(Resource, Reflect, Debug, Default)]
+#[reflect(Resource)]
+pub struct VirtualInput {
+    pub pressed: HashSet<Action>,
+    /// Horizontal position of a virtual joystick, between -1 and 1.
+    pub horizontal: f32,
+}
+
+/// Run condition that is true on the frame an action is pressed.
+pub fn action_just_pressed(action: Action) -> impl FnMut(Res<ActionState>) -> bool + Clone {
+    move |action_state: Res<ActionState>| action_state.just_pressed(action)
+}
+
+fn update_action_state(
+    bindings: Res<InputBindings>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    gamepad_query: Query<&Gamepad>,
+    virtual_input: Res<VirtualInput>,
+    mut action_state: ResMut<ActionState>,
+) {
+    let pressed: HashSet<_> = Action::ALL
+        .into_iter()
+        .filter(|&action| {
+            virtual_input.pressed.contains(&action)
+                || bindings.get(action).iter().any(|binding| match binding {
+                    Binding::Key(key) => keyboard.pressed(*key),
+                    Binding::Mouse(button) => mouse.pressed(*button),
+                    Binding::Gamepad(button) => {
+                        gamepad_query.iter().any(|gamepad| gamepad.pressed(*button))
+                    }
+                })
+        })
+        .collect();
+    action_state.just_pressed = pressed.difference(&action_state.pressed).copied().collect();
+    action_state.pressed = pressed;
+
+    // Use whichever stick is pushed furthest, rescaled so movement starts
+    // from zero at the edge of the deadzone.
+    let stick = gamepad_query
+        .iter()
+        .map(|gamepad| gamepad.left_stick().x)
+        .chain([virtual_input.horizontal])
+        .max_by(|a, b| a.abs().total_cmp(&b.abs()))
+        .unwrap_or_default();
+    let deadzone = ActionState::STICK_DEADZONE;
+    action_state.stick =
+        stick.signum() * ((stick.abs() - deadzone) / (1.0 - deadzone)).clamp(0.0, 1.0);
+}
+
+/// The action waiting for a new binding, if any.
+///
+/// The next key, mouse button or gamepad button pressed replaces the action's
+/// bindings on that kind of device. Escape or the gamepad's Select button cancels without changing
+/// anything.
+#[derive(Resource, Reflect, Debug, Default)]
+#[reflect(Resource)]
+pub struct Rebinding(pub Option<Action>);
+
+fn capture_rebinding(
+    mut rebinding: ResMut<Rebinding>,
+    mut bindings: ResMut<InputBindings>,
+    mut keyboard: ResMut<ButtonInput<KeyCode>>,
+    mut mouse: ResMut<ButtonInput<MouseButton>>,
+    mut gamepad_query: Query<&mut Gamepad>,
+) {
+    let Some(action) = rebinding.0 else {
+        return;
+    };
+    let key = keyboard.get_just_pressed().next().copied();
+    let button = mouse.get_just_pressed().next().copied();
+    let gamepad_button = gamepad_query.iter_mut().find_map(|mut gamepad| {
+        let button = gamepad.get_just_pressed().next().copied()?;
+        gamepad.digital_mut().reset(button);
+        Some(button)
+    });
+    let binding = if let Some(key) = key {
+        // Don't let the key do anything else this frame, e.g. close the menu.
+        keyboard.reset(key);
+        if key == KeyCode::Escape {
+            rebinding.0 = None;
+            return;
+        }
+        Binding::Key(key)
+    } else if let Some(button) = button {
+        mouse.reset(button);
+        Binding::Mouse(button)
+    } else if let Some(button) = gamepad_button {
+        if button == GamepadButton::Select {
+            rebinding.0 = None;
+            return;
+        }
+        Binding::Gamepad(button)
+    } else {
+        return;
+    };
+    bindings.rebind(action, binding);
+    bindings.save();
+    rebinding.0 = None;
+}
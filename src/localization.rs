@@ -0,0 +1,223 @@
+//! Showing text in the player's language.
+//!
+//! Text is written in English in code and assets, and the English text is the
+//! key it is translated by: a [`StringTable`] (`assets/locales/*.strings.ron`)
+//! maps it to another language, and anything missing from the table stays
+//! English. Placeholders name an [`Action`] like `{Eat}` to show its current
+//! binding, or an argument passed to [`Localization::format`] like `{tier}`.
+//!
+//! Entities with a [`Localized`] component have their [`Text`] or [`Text2d`]
+//! rendered from it, and re-rendered whenever the language, the bindings or a
+//! string table (hot reloaded in native dev builds) change. Text built at
+//! runtime reads the [`Localization`] system param instead, checking
+//! [`Localization::is_changed`] to know when to rebuild it.
+
+use std::collections::HashMap;
+
+use bevy::{
+    asset::{AssetLoader, LoadContext, io::Reader},
+    ecs::system::SystemParam,
+    prelude::*,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    asset_tracking::LoadResource,
+    input::{Action, InputBindings},
+    settings::Settings,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_asset::<StringTable>();
+    app.register_asset_loader(StringTableLoader);
+    app.load_resource::<LocaleAssets>();
+    app.init_resource::<ActiveLanguage>();
+    app.add_systems(
+        PostUpdate,
+        (update_active_language, localize_texts)
+            .chain()
+            .before(bevy::ui::UiSystems::Layout),
+    );
+}
+
+#[derive(Reflect, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Language {
+    #[default]
+    English,
+    German,
+    Spanish,
+}
+
+impl Language {
+    pub const ALL: [Self; 3] = [Self::English, Self::German, Self::Spanish];
+
+    /// Name of the language in that language, so players can always find theirs.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::English => "English",
+            Self::German => "Deutsch",
+            Self::Spanish => "Español",
+        }
+    }
+}
+
+/// Translations of English text into one language.
+#[derive(Asset, Reflect, Debug, Deserialize)]
+#[serde(transparent)]
+pub struct StringTable(HashMap<String, String>);
+
+#[derive(TypePath, Default)]
+struct StringTableLoader;
+
+impl AssetLoader for StringTableLoader {
+    type Asset = StringTable;
+    type Settings = ();
+    type Error = BevyError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(ron::de::from_bytes(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["strings.ron"]
+    }
+}
+
+#[derive(Resource, Asset, Clone, Reflect)]
+#[reflect(Resource)]
+pub struct LocaleAssets {
+    #[dependency]
+    german: Handle<StringTable>,
+    #[dependency]
+    spanish: Handle<StringTable>,
+}
+
+impl LocaleAssets {
+    /// The table for a language, or `None` for English, which needs none.
+    fn get(&self, language: Language) -> Option<&Handle<StringTable>> {
+        match language {
+            Language::English => None,
+            Language::German => Some(&self.german),
+            Language::Spanish => Some(&self.spanish),
+        }
+    }
+}
+
+impl FromWorld for LocaleAssets {
+    fn from_world(world: &mut World) -> Self {
+        let assets = world.resource::<AssetServer>();
+        Self {
+            german: assets.load("locales/de.strings.ron"),
+            spanish: assets.load("locales/es.strings.ron"),
+        }
+    }
+}
+
+/// The language text is shown in. Follows [`Settings::language`], and is also
+/// marked changed when a string table loads or is edited.
+#[derive(Resource, Reflect, Debug, Default, PartialEq)]
+#[reflect(Resource)]
+pub struct ActiveLanguage(pub Language);
+
+fn update_active_language(
+    settings: Res<Settings>,
+    locale_assets: Option<Res<LocaleAssets>>,
+    mut table_events: MessageReader<AssetEvent<StringTable>>,
+    mut active: ResMut<ActiveLanguage>,
+) {
+    active.set_if_neq(ActiveLanguage(settings.language));
+    let tables_changed = table_events.read().count() > 0;
+    if tables_changed || locale_assets.is_some_and(|assets| assets.is_added()) {
+        active.set_changed();
+    }
+}
+
+/// Looks up text in the current language.
+#[derive(SystemParam)]
+pub struct Localization<'w> {
+    language: Res<'w, ActiveLanguage>,
+    bindings: Res<'w, InputBindings>,
+    locale_assets: Option<Res<'w, LocaleAssets>>,
+    tables: Res<'w, Assets<StringTable>>,
+}
+
+impl Localization<'_> {
+    /// Whether text from this may have changed since the system last ran.
+    pub fn is_changed(&self) -> bool {
+        self.language.is_changed() || self.bindings.is_changed()
+    }
+
+    /// The translation of `text` with action placeholders filled in.
+    pub fn get(&self, text: &str) -> String {
+        self.format(text, &[])
+    }
+
+    /// The translation of `text` with `{name}` placeholders replaced by `args`
+    /// and action placeholders filled in.
+    pub fn format(&self, text: &str, args: &[(&str, &str)]) -> String {
+        let mut text = self.translate(text).to_string();
+        for (name, value) in args {
+            text = text.replace(&format!("{{{name}}}"), value);
+        }
+        for action in Action::ALL {
+            let placeholder = format!("{{{action:?}}}");
+            if text.contains(&placeholder) {
+                let binding = self.translate(&self.bindings.label(action)).to_string();
+                text = text.replace(&placeholder, &binding);
+            }
+        }
+        text
+    }
+
+    fn translate<'a>(&'a self, text: &'a str) -> &'a str {
+        self.locale_assets
+            .as_ref()
+            .and_then(|assets| assets.get(self.language.0))
+            .and_then(|handle| self.tables.get(handle))
+            .and_then(|table| table.0.get(text))
+            .map_or(text, String::as_str)
+    }
+}
+
+/// English text shown in the player's language in this entity's [`Text`] or
+/// [`Text2d`]. Empty text is left alone, so widgets whose text is set at
+/// runtime can still be localized by hand.
+#[derive(Component, Reflect, Debug, Clone)]
+#[reflect(Component)]
+pub struct Localized(pub String);
+
+impl Localized {
+    pub fn new(text: impl Into<String>) -> Self {
+        Self(text.into())
+    }
+}
+
+fn localize_texts(
+    localization: Localization,
+    mut text_query: Query<(Ref<Localized>, Option<&mut Text>, Option<&mut Text2d>)>,
+) {
+    let rerender_all = localization.is_changed();
+    for (localized, text, text_2d) in &mut text_query {
+        if localized.0.is_empty() || !rerender_all && !localized.is_changed() {
+            continue;
+        }
+        let value = localization.get(&localized.0);
+        if let Some(mut text) = text
+            && text.0 != value
+        {
+            text.0 = value.clone();
+        }
+        if let Some(mut text) = text_2d
+            && text.0 != value
+        {
+            text.0 = value;
+        }
+    }
+}
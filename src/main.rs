@@ -8,12 +8,24 @@ mod audio;
 mod demo;
 #[cfg(feature = "dev")]
 mod dev_tools;
+mod input;
+mod localization;
 mod menus;
+mod persistence;
+mod screen_effect;
 mod screens;
+mod settings;
 mod theme;
+mod touch;
+mod viewport;
 
 use bevy::{asset::AssetMetaCheck, camera::ScalingMode, prelude::*};
 
+use crate::{
+    screen_effect::ScreenEffect,
+    viewport::{MainCamera, VirtualResolution},
+};
+
 fn main() -> AppExit {
     App::new().add_plugins(AppPlugin).run()
 }
@@ -50,9 +62,15 @@ impl Plugin for AppPlugin {
             demo::plugin,
             #[cfg(feature = "dev")]
             dev_tools::plugin,
+            input::plugin,
+            localization::plugin,
             menus::plugin,
+            screen_effect::plugin,
             screens::plugin,
+            settings::plugin,
             theme::plugin,
+            touch::plugin,
+            viewport::plugin,
         ));
 
         // Order new `AppSystems` variants by adding them here:
@@ -96,10 +114,13 @@ struct Pause(pub bool);
 #[derive(SystemSet, Copy, Clone, Eq, PartialEq, Hash, Debug)]
 struct PausableSystems;
 
-fn spawn_camera(mut commands: Commands) {
+fn spawn_camera(mut commands: Commands, resolution: Res<VirtualResolution>) {
     commands.spawn((
         Name::new("Camera"),
+        MainCamera,
         Camera2d,
+        resolution.projection(),
+        ScreenEffect::default(),
         Transform::from_xyz(0.0, -24.0, 0.0),
     ));
 }
@@ -1,14 +1,22 @@
 //! The credits menu.
 
-use bevy::{ecs::spawn::SpawnIter, input::common_conditions::input_just_pressed, prelude::*};
+use bevy::{ecs::spawn::SpawnIter, prelude::*};
 
-use crate::{asset_tracking::LoadResource, audio::music, menus::Menu, theme::prelude::*};
+use crate::{
+    asset_tracking::LoadResource,
+    audio::music,
+    menus::Menu,
+    theme::{
+        navigation::{Activate, menu_back_just_pressed},
+        prelude::*,
+    },
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Menu::Credits), spawn_credits_menu);
     app.add_systems(
         Update,
-        go_back.run_if(in_state(Menu::Credits).and(input_just_pressed(KeyCode::Escape))),
+        go_back.run_if(in_state(Menu::Credits).and(menu_back_just_pressed)),
     );
 
     app.load_resource::<CreditsAssets>();
@@ -21,10 +29,25 @@ fn spawn_credits_menu(mut commands: Commands) {
         GlobalZIndex(2),
         DespawnOnExit(Menu::Credits),
         children![
-            widget::header("Created by"),
-            created_by(),
-            widget::header("Assets"),
-            assets(),
+            widget::scroll_list(
+                "Credits List",
+                px(440),
+                (
+                    Name::new("Credits"),
+                    Node {
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        row_gap: px(20),
+                        ..default()
+                    },
+                    children![
+                        widget::header("Created by"),
+                        created_by(),
+                        widget::header("Assets"),
+                        assets(),
+                    ],
+                ),
+            ),
             widget::button("Back", go_back_on_click),
         ],
     ));
@@ -77,7 +100,7 @@ fn grid(content: Vec<[&'static str; 2]>) -> impl Bundle {
     )
 }
 
-fn go_back_on_click(_: On<Pointer<Click>>, mut next_menu: If<ResMut<NextState<Menu>>>) {
+fn go_back_on_click(_: On<Activate>, mut next_menu: If<ResMut<NextState<Menu>>>) {
     next_menu.set(Menu::Main);
 }
 
@@ -2,7 +2,12 @@
 
 use bevy::prelude::*;
 
-use crate::{asset_tracking::ResourceHandles, menus::Menu, screens::Screen, theme::widget};
+use crate::{
+    asset_tracking::ResourceHandles,
+    menus::Menu,
+    screens::Screen,
+    theme::{navigation::Activate, widget},
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Menu::Main), spawn_main_menu);
@@ -30,7 +35,7 @@ fn spawn_main_menu(mut commands: Commands) {
 }
 
 fn enter_loading_or_gameplay_screen(
-    _: On<Pointer<Click>>,
+    _: On<Activate>,
     resource_handles: If<Res<ResourceHandles>>,
     mut next_screen: If<ResMut<NextState<Screen>>>,
 ) {
@@ -41,15 +46,15 @@ fn enter_loading_or_gameplay_screen(
     }
 }
 
-fn open_settings_menu(_: On<Pointer<Click>>, mut next_menu: If<ResMut<NextState<Menu>>>) {
+fn open_settings_menu(_: On<Activate>, mut next_menu: If<ResMut<NextState<Menu>>>) {
     next_menu.set(Menu::Settings);
 }
 
-fn open_credits_menu(_: On<Pointer<Click>>, mut next_menu: If<ResMut<NextState<Menu>>>) {
+fn open_credits_menu(_: On<Activate>, mut next_menu: If<ResMut<NextState<Menu>>>) {
     next_menu.set(Menu::Credits);
 }
 
 #[cfg(not(target_family = "wasm"))]
-fn exit_app(_: On<Pointer<Click>>, mut app_exit: MessageWriter<AppExit>) {
+fn exit_app(_: On<Activate>, mut app_exit: MessageWriter<AppExit>) {
     app_exit.write(AppExit::Success);
 }
@@ -1,11 +1,22 @@
 //! The pause menu.
 
-use bevy::{input::common_conditions::input_just_pressed, prelude::*};
+use bevy::prelude::*;
 
-use crate::{menus::Menu, screens::Screen, theme::widget};
+use crate::{
+    menus::Menu,
+    screens::Screen,
+    theme::{
+        navigation::{Activate, menu_back_just_pressed},
+        widget,
+    },
+};
 
 pub(super) fn plugin(app: &mut App) {
-
+    app.add_systems(OnEnter(Menu::Pause), spawn_pause_menu);
+    app.add_systems(
+        Update,
+        go_back.run_if(in_state(Menu::Pause).and(menu_back_just_pressed)),
+    );
 }
 
 fn spawn_pause_menu(mut commands: Commands) {
@@ -22,15 +33,15 @@ fn spawn_pause_menu(mut commands: Commands) {
     ));
 }
 
-fn open_settings_menu(_: On<Pointer<Click>>, mut next_menu: If<ResMut<NextState<Menu>>>) {
+fn open_settings_menu(_: On<Activate>, mut next_menu: If<ResMut<NextState<Menu>>>) {
     next_menu.set(Menu::Settings);
 }
 
-fn close_menu(_: On<Pointer<Click>>, mut next_menu: If<ResMut<NextState<Menu>>>) {
+fn close_menu(_: On<Activate>, mut next_menu: If<ResMut<NextState<Menu>>>) {
     next_menu.set(Menu::None);
 }
 
-fn quit_to_title(_: On<Pointer<Click>>, mut next_screen: If<ResMut<NextState<Screen>>>) {
+fn quit_to_title(_: On<Activate>, mut next_screen: If<ResMut<NextState<Screen>>>) {
     next_screen.set(Screen::Title);
 }
 
@@ -1,110 +1,441 @@
 //! The settings menu.
 //!
-//! Additional settings and accessibility options should go here.
+//! Audio, display, gameplay and accessibility options on the left, key
+//! bindings on the right, scrolling when they don't fit the window.
 
-use bevy::{audio::Volume, input::common_conditions::input_just_pressed, prelude::*};
+use bevy::{ecs::spawn::SpawnWith, prelude::*};
 
-use crate::{menus::Menu, screens::Screen, theme::prelude::*};
+use crate::{
+    input::{Action, InputBindings, Rebinding},
+    localization::{Language, Localization},
+    menus::Menu,
+    screens::Screen,
+    settings::{ColorblindMode, Difficulty, Settings, UiTheme},
+    theme::{
+        navigation::{Activate, menu_back_just_pressed},
+        prelude::*,
+        style::ThemedNode,
+        widget::ValueChanged,
+    },
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Menu::Settings), spawn_settings_menu);
+    app.add_systems(OnExit(Menu::Settings), (cancel_rebinding, save_settings));
     app.add_systems(
         Update,
-        go_back.run_if(in_state(Menu::Settings).and(input_just_pressed(KeyCode::Escape))),
+        go_back.run_if(in_state(Menu::Settings).and(menu_back_just_pressed)),
     );
 
     app.add_systems(
         Update,
-        update_global_volume_label.run_if(in_state(Menu::Settings)),
+        update_binding_labels.run_if(in_state(Menu::Settings)),
     );
 }
 
-fn spawn_settings_menu(mut commands: Commands) {
+fn spawn_settings_menu(mut commands: Commands, settings: Res<Settings>) {
     commands.spawn((
         widget::ui_root("Settings Menu"),
         GlobalZIndex(2),
         DespawnOnExit(Menu::Settings),
         children![
             widget::header("Settings"),
-            settings_grid(),
+            widget::scroll_list(
+                "Settings List",
+                px(520),
+                (
+                    Name::new("Settings Columns"),
+                    Node {
+                        column_gap: px(60),
+                        align_items: AlignItems::FlexStart,
+                        ..default()
+                    },
+                    children![settings_grid(&settings), controls_grid()],
+                ),
+            ),
             widget::button("Back", go_back_on_click),
         ],
     ));
 }
 
-fn settings_grid() -> impl Bundle {
+fn settings_grid(settings: &Settings) -> impl Bundle {
+    let settings = settings.clone();
     (
         Name::new("Settings Grid"),
+        ThemedNode::Grid,
         Node {
             display: Display::Grid,
-            row_gap: px(10),
+            row_gap: px(8),
             column_gap: px(30),
-            grid_template_columns: RepeatedGridTrack::px(2, 400.0),
+            grid_template_columns: vec![GridTrack::px(200.0), GridTrack::px(240.0)],
             ..default()
         },
-        children![
-            (
-                widget::label("Master Volume"),
-                Node {
-                    justify_self: JustifySelf::End,
-                    ..default()
-                }
-            ),
-            global_volume_widget(),
-        ],
+        Children::spawn(SpawnWith(move |parent: &mut ChildSpawner| {
+            setting_row(
+                parent,
+                "Master Volume",
+                widget::slider(
+                    settings.master_volume,
+                    0.0,
+                    Settings::MAX_VOLUME,
+                    0.1,
+                    |change: On<ValueChanged<f32>>, mut settings: ResMut<Settings>| {
+                        settings.master_volume = change.value;
+                    },
+                ),
+            );
+            setting_row(
+                parent,
+                "Music Volume",
+                widget::slider(
+                    settings.music_volume,
+                    0.0,
+                    1.0,
+                    0.1,
+                    |change: On<ValueChanged<f32>>, mut settings: ResMut<Settings>| {
+                        settings.music_volume = change.value;
+                    },
+                ),
+            );
+            setting_row(
+                parent,
+                "SFX Volume",
+                widget::slider(
+                    settings.sfx_volume,
+                    0.0,
+                    1.0,
+                    0.1,
+                    |change: On<ValueChanged<f32>>, mut settings: ResMut<Settings>| {
+                        settings.sfx_volume = change.value;
+                    },
+                ),
+            );
+            setting_row(
+                parent,
+                "Fullscreen",
+                widget::toggle(
+                    settings.fullscreen,
+                    |change: On<ValueChanged<bool>>, mut settings: ResMut<Settings>| {
+                        settings.fullscreen = change.value;
+                    },
+                ),
+            );
+            // Browsers decide the canvas size and frame pacing themselves.
+            #[cfg(not(target_family = "wasm"))]
+            {
+                let scales = Settings::RESOLUTION_SCALES;
+                let resolutions = scales
+                    .iter()
+                    .map(|scale| {
+                        let size = Settings::BASE_RESOLUTION.as_vec2() * scale;
+                        format!("{}x{}", size.x, size.y)
+                    })
+                    .collect();
+                let selected = scales
+                    .iter()
+                    .position(|&scale| scale == settings.resolution_scale)
+                    .unwrap_or_default();
+                setting_row(
+                    parent,
+                    "Resolution",
+                    widget::selector(
+                        resolutions,
+                        selected,
+                        move |change: On<ValueChanged<usize>>, mut settings: ResMut<Settings>| {
+                            settings.resolution_scale = scales[change.value];
+                        },
+                    ),
+                );
+                setting_row(
+                    parent,
+                    "VSync",
+                    widget::toggle(
+                        settings.vsync,
+                        |change: On<ValueChanged<bool>>, mut settings: ResMut<Settings>| {
+                            settings.vsync = change.value;
+                        },
+                    ),
+                );
+            }
+            setting_row(
+                parent,
+                "Pixel Perfect",
+                widget::toggle(
+                    settings.pixel_perfect,
+                    |change: On<ValueChanged<bool>>, mut settings: ResMut<Settings>| {
+                        settings.pixel_perfect = change.value;
+                    },
+                ),
+            );
+            setting_row(
+                parent,
+                "Screen Shake",
+                widget::slider(
+                    settings.screen_shake,
+                    0.0,
+                    1.0,
+                    0.1,
+                    |change: On<ValueChanged<f32>>, mut settings: ResMut<Settings>| {
+                        settings.screen_shake = change.value;
+                    },
+                ),
+            );
+            let difficulties = Difficulty::ALL
+                .iter()
+                .map(|difficulty| difficulty.label().to_string())
+                .collect();
+            let selected = Difficulty::ALL
+                .iter()
+                .position(|&difficulty| difficulty == settings.difficulty)
+                .unwrap_or_default();
+            setting_row(
+                parent,
+                "Difficulty",
+                widget::selector(
+                    difficulties,
+                    selected,
+                    |change: On<ValueChanged<usize>>, mut settings: ResMut<Settings>| {
+                        settings.difficulty = Difficulty::ALL[change.value];
+                    },
+                ),
+            );
+            setting_row(
+                parent,
+                "Hunger",
+                widget::toggle(
+                    settings.hunger,
+                    |change: On<ValueChanged<bool>>, mut settings: ResMut<Settings>| {
+                        settings.hunger = change.value;
+                    },
+                ),
+            );
+            let languages = Language::ALL
+                .iter()
+                .map(|language| language.label().to_string())
+                .collect();
+            let selected = Language::ALL
+                .iter()
+                .position(|&language| language == settings.language)
+                .unwrap_or_default();
+            setting_row(
+                parent,
+                "Language",
+                widget::selector(
+                    languages,
+                    selected,
+                    |change: On<ValueChanged<usize>>, mut settings: ResMut<Settings>| {
+                        settings.language = Language::ALL[change.value];
+                    },
+                ),
+            );
+            let themes = UiTheme::ALL
+                .iter()
+                .map(|theme| theme.label().to_string())
+                .collect();
+            let selected = UiTheme::ALL
+                .iter()
+                .position(|&theme| theme == settings.theme)
+                .unwrap_or_default();
+            setting_row(
+                parent,
+                "Theme",
+                widget::selector(
+                    themes,
+                    selected,
+                    |change: On<ValueChanged<usize>>, mut settings: ResMut<Settings>| {
+                        settings.theme = UiTheme::ALL[change.value];
+                    },
+                ),
+            );
+            setting_row(
+                parent,
+                "Text Size",
+                widget::slider(
+                    settings.text_scale,
+                    0.8,
+                    1.4,
+                    0.1,
+                    |change: On<ValueChanged<f32>>, mut settings: ResMut<Settings>| {
+                        settings.text_scale = change.value;
+                    },
+                ),
+            );
+            let colorblind_modes = ColorblindMode::ALL
+                .iter()
+                .map(|mode| mode.label().to_string())
+                .collect();
+            let selected = ColorblindMode::ALL
+                .iter()
+                .position(|&mode| mode == settings.colorblind_mode)
+                .unwrap_or_default();
+            setting_row(
+                parent,
+                "Colorblind Mode",
+                widget::selector(
+                    colorblind_modes,
+                    selected,
+                    |change: On<ValueChanged<usize>>, mut settings: ResMut<Settings>| {
+                        settings.colorblind_mode = ColorblindMode::ALL[change.value];
+                    },
+                ),
+            );
+            setting_row(
+                parent,
+                "Reduced Motion",
+                widget::toggle(
+                    settings.reduced_motion,
+                    |change: On<ValueChanged<bool>>, mut settings: ResMut<Settings>| {
+                        settings.reduced_motion = change.value;
+                    },
+                ),
+            );
+            setting_row(
+                parent,
+                "Toggle Glide",
+                widget::toggle(
+                    settings.toggle_glide,
+                    |change: On<ValueChanged<bool>>, mut settings: ResMut<Settings>| {
+                        settings.toggle_glide = change.value;
+                    },
+                ),
+            );
+            setting_row(
+                parent,
+                "Game Speed",
+                widget::slider(
+                    settings.game_speed,
+                    0.5,
+                    1.0,
+                    0.1,
+                    |change: On<ValueChanged<f32>>, mut settings: ResMut<Settings>| {
+                        settings.game_speed = change.value;
+                    },
+                ),
+            );
+        })),
     )
 }
 
-fn global_volume_widget() -> impl Bundle {
-    (
-        Name::new("Global Volume Widget"),
+/// Spawn a label and the widget that changes its setting into a settings grid.
+fn setting_row(parent: &mut ChildSpawner, label: &str, widget: impl Bundle) {
+    parent.spawn((
+        widget::label(label),
+        Node {
+            justify_self: JustifySelf::End,
+            align_self: AlignSelf::Center,
+            ..default()
+        },
+    ));
+    parent.spawn((
+        Name::new("Setting"),
         Node {
             justify_self: JustifySelf::Start,
             ..default()
         },
-        children![
-            widget::button_small("-", lower_global_volume),
-            (
-                Name::new("Current Volume"),
+        children![widget],
+    ));
+}
+
+fn save_settings(settings: Res<Settings>) {
+    settings.save();
+}
+
+fn controls_grid() -> impl Bundle {
+    (
+        Name::new("Controls Grid"),
+        Node {
+            display: Display::Grid,
+            row_gap: px(6),
+            column_gap: px(30),
+            grid_template_columns: vec![GridTrack::px(180.0), GridTrack::px(280.0)],
+            ..default()
+        },
+        Children::spawn(SpawnWith(|parent: &mut ChildSpawner| {
+            parent.spawn((
+                widget::label("Controls"),
                 Node {
-                    padding: UiRect::horizontal(px(10)),
-                    justify_content: JustifyContent::Center,
+                    justify_self: JustifySelf::End,
+                    align_self: AlignSelf::Center,
                     ..default()
                 },
-                children![(widget::label(""), GlobalVolumeLabel)],
-            ),
-            widget::button_small("+", raise_global_volume),
-        ],
+            ));
+            parent.spawn((
+                Name::new("Reset Bindings"),
+                Node {
+                    justify_self: JustifySelf::Start,
+                    ..default()
+                },
+                children![widget::button_medium("Reset to defaults", reset_bindings)],
+            ));
+            for action in Action::ALL {
+                parent.spawn((
+                    widget::label(action.label()),
+                    Node {
+                        justify_self: JustifySelf::End,
+                        align_self: AlignSelf::Center,
+                        ..default()
+                    },
+                ));
+                parent.spawn((
+                    Name::new("Binding"),
+                    Node {
+                        justify_self: JustifySelf::Start,
+                        ..default()
+                    },
+                    children![(
+                        RebindButton(action),
+                        widget::button_medium(
+                            "",
+                            move |_: On<Activate>, mut rebinding: ResMut<Rebinding>| {
+                                rebinding.0 = Some(action);
+                            }
+                        ),
+                    )],
+                ));
+            }
+        })),
     )
 }
 
-const MIN_VOLUME: f32 = 0.0;
-const MAX_VOLUME: f32 = 3.0;
+/// Shows the bindings of an action, and waits for a new one when clicked.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct RebindButton(Action);
 
-fn lower_global_volume(_: On<Pointer<Click>>, mut global_volume: If<ResMut<GlobalVolume>>) {
-    let linear = (global_volume.volume.to_linear() - 0.1).max(MIN_VOLUME);
-    global_volume.volume = Volume::Linear(linear);
+fn reset_bindings(_: On<Activate>, mut input_bindings: ResMut<InputBindings>) {
+    *input_bindings = InputBindings::default();
+    input_bindings.save();
 }
 
-fn raise_global_volume(_: On<Pointer<Click>>, mut global_volume: If<ResMut<GlobalVolume>>) {
-    let linear = (global_volume.volume.to_linear() + 0.1).min(MAX_VOLUME);
-    global_volume.volume = Volume::Linear(linear);
+fn cancel_rebinding(mut rebinding: ResMut<Rebinding>) {
+    rebinding.0 = None;
 }
 
-#[derive(Component, Reflect)]
-#[reflect(Component)]
-struct GlobalVolumeLabel;
-
-fn update_global_volume_label(
-    global_volume: If<Res<GlobalVolume>>,
-    mut label: Single<&mut Text, With<GlobalVolumeLabel>>,
+fn update_binding_labels(
+    input_bindings: Res<InputBindings>,
+    localization: Localization,
+    rebinding: Res<Rebinding>,
+    button_query: Query<(Entity, &RebindButton)>,
+    children_query: Query<&Children>,
+    mut text_query: Query<&mut Text>,
 ) {
-    let percent = 100.0 * global_volume.volume.to_linear();
-    label.0 = format!("{percent:3.0}%");
+    for (entity, button) in &button_query {
+        let label = if rebinding.0 == Some(button.0) {
+            localization.get("Press a key...")
+        } else {
+            localization.get(&input_bindings.label(button.0))
+        };
+        let mut texts = text_query.iter_many_mut(children_query.iter_descendants(entity));
+        while let Some(mut text) = texts.fetch_next() {
+            if text.0 != label {
+                text.0 = label.clone();
+            }
+        }
+    }
 }
 
 fn go_back_on_click(
-    _: On<Pointer<Click>>,
+    _: On<Activate>,
     screen: If<Res<State<Screen>>>,
     mut next_menu: If<ResMut<NextState<Menu>>>,
 ) {
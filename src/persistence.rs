@@ -0,0 +1,66 @@
+//! Keeping small pieces of data, like input bindings, between runs.
+//!
+//! Values are written as RON files to a `saves` directory in the working
+//! directory. Web builds have no file system, so they keep the same RON text
+//! in the browser's local storage, keyed by the save name.
+
+use serde::{Serialize, de::DeserializeOwned};
+
+#[cfg(not(target_family = "wasm"))]
+const SAVE_DIR: &str = "saves";
+
+/// Load a value saved under `name`, or `None` if there isn't a valid one.
+#[cfg(not(target_family = "wasm"))]
+pub fn load<T: DeserializeOwned>(name: &str) -> Option<T> {
+    let path = std::path::Path::new(SAVE_DIR).join(format!("{name}.ron"));
+    let text = std::fs::read_to_string(&path).ok()?;
+    ron::from_str(&text)
+        .inspect_err(|error| bevy::log::warn!("Ignoring invalid {}: {error}", path.display()))
+        .ok()
+}
+
+/// Save a value under `name`, replacing what was saved before.
+#[cfg(not(target_family = "wasm"))]
+pub fn save<T: Serialize>(name: &str, value: &T) {
+    let path = std::path::Path::new(SAVE_DIR).join(format!("{name}.ron"));
+    let result = ron::ser::to_string_pretty(value, ron::ser::PrettyConfig::default())
+        .map_err(|error| error.to_string())
+        .and_then(|text| {
+            std::fs::create_dir_all(SAVE_DIR)
+                .and_then(|()| std::fs::write(&path, text))
+                .map_err(|error| error.to_string())
+        });
+    if let Err(error) = result {
+        bevy::log::warn!("Failed to save {}: {error}", path.display());
+    }
+}
+
+#[cfg(target_family = "wasm")]
+fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok().flatten()
+}
+
+/// Load a value saved under `name`, or `None` if there isn't a valid one.
+#[cfg(target_family = "wasm")]
+pub fn load<T: DeserializeOwned>(name: &str) -> Option<T> {
+    let text = local_storage()?.get_item(name).ok().flatten()?;
+    ron::from_str(&text)
+        .inspect_err(|error| bevy::log::warn!("Ignoring invalid saved {name}: {error}"))
+        .ok()
+}
+
+/// Save a value under `name`, replacing what was saved before.
+#[cfg(target_family = "wasm")]
+pub fn save<T: Serialize>(name: &str, value: &T) {
+    let result = ron::ser::to_string_pretty(value, ron::ser::PrettyConfig::default())
+        .map_err(|error| error.to_string())
+        .and_then(|text| {
+            local_storage()
+                .ok_or_else(|| "local storage is unavailable".to_string())?
+                .set_item(name, &text)
+                .map_err(|error| format!("{error:?}"))
+        });
+    if let Err(error) = result {
+        bevy::log::warn!("Failed to save {name}: {error}");
+    }
+}
@@ -0,0 +1,200 @@
+//! A full-screen post-processing pass for color effects.
+//!
+//! Cameras with a [`ScreenEffect`] get a vignette, a color tint, contrast and
+//! desaturation applied after the main 2D pass. Gameplay code only has to
+//! change the component; it is extracted to the render world every frame.
+//!
+//! This follows Bevy's `custom_post_processing` example, adapted for 2D.
+
+use bevy::{
+    core_pipeline::{
+        FullscreenShader,
+        core_2d::graph::{Core2d, Node2d},
+    },
+    ecs::query::QueryItem,
+    prelude::*,
+    render::{
+        RenderApp, RenderStartup,
+        extract_component::{
+            ComponentUniforms, DynamicUniformIndex, ExtractComponent, ExtractComponentPlugin,
+            UniformComponentPlugin,
+        },
+        render_graph::{
+            NodeRunError, RenderGraphContext, RenderGraphExt, RenderLabel, ViewNode, ViewNodeRunner,
+        },
+        render_resource::{
+            binding_types::{sampler, texture_2d, uniform_buffer},
+            *,
+        },
+        renderer::{RenderContext, RenderDevice},
+        view::ViewTarget,
+    },
+};
+
+const SHADER_ASSET_PATH: &str = "shaders/screen_effect.wgsl";
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_plugins((
+        ExtractComponentPlugin::<ScreenEffect>::default(),
+        UniformComponentPlugin::<ScreenEffect>::default(),
+    ));
+
+    let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+        return;
+    };
+    render_app
+        .add_systems(RenderStartup, init_screen_effect_pipeline)
+        .add_render_graph_node::<ViewNodeRunner<ScreenEffectNode>>(Core2d, ScreenEffectLabel)
+        .add_render_graph_edges(
+            Core2d,
+            (
+                Node2d::Tonemapping,
+                ScreenEffectLabel,
+                Node2d::EndMainPassPostProcessing,
+            ),
+        );
+}
+
+/// Color effects applied to everything a camera renders.
+///
+/// The default leaves the image untouched.
+#[derive(Component, Reflect, Debug, Clone, Copy, ExtractComponent, ShaderType)]
+#[reflect(Component)]
+pub struct ScreenEffect {
+    /// Color the image is multiplied by. Alpha is how strongly it applies.
+    pub tint: LinearRgba,
+    /// How dark the corners get, between 0 and 1.
+    pub vignette: f32,
+    /// How much of the tint bleeds into the vignette, between 0 and 1.
+    pub vignette_tint: f32,
+    /// 0 keeps the colors, 1 turns the image grey.
+    pub desaturation: f32,
+    /// 1 keeps the contrast, higher values push colors away from mid grey.
+    pub contrast: f32,
+}
+
+impl Default for ScreenEffect {
+    fn default() -> Self {
+        Self {
+            tint: LinearRgba::new(1.0, 1.0, 1.0, 0.0),
+            vignette: 0.0,
+            vignette_tint: 0.0,
+            desaturation: 0.0,
+            contrast: 1.0,
+        }
+    }
+}
+
+#[derive(Debug, Hash, PartialEq, Eq, Clone, RenderLabel)]
+struct ScreenEffectLabel;
+
+#[derive(Default)]
+struct ScreenEffectNode;
+
+impl ViewNode for ScreenEffectNode {
+    type ViewQuery = (
+        &'static ViewTarget,
+        &'static ScreenEffect,
+        &'static DynamicUniformIndex<ScreenEffect>,
+    );
+
+    fn run(
+        &self,
+        _graph: &mut RenderGraphContext,
+        render_context: &mut RenderContext,
+        (view_target, _screen_effect, settings_index): QueryItem<Self::ViewQuery>,
+        world: &World,
+    ) -> Result<(), NodeRunError> {
+        let screen_effect_pipeline = world.resource::<ScreenEffectPipeline>();
+        let pipeline_cache = world.resource::<PipelineCache>();
+        let Some(pipeline) = pipeline_cache.get_render_pipeline(screen_effect_pipeline.pipeline_id)
+        else {
+            return Ok(());
+        };
+        let settings_uniforms = world.resource::<ComponentUniforms<ScreenEffect>>();
+        let Some(settings_binding) = settings_uniforms.uniforms().binding() else {
+            return Ok(());
+        };
+
+        // Reads the current main texture and writes to the other one, which
+        // then becomes the main texture.
+        let post_process = view_target.post_process_write();
+        // The source changes with every write, so the bind group can't be cached.
+        let bind_group = render_context.render_device().create_bind_group(
+            "screen_effect_bind_group",
+            &pipeline_cache.get_bind_group_layout(&screen_effect_pipeline.layout),
+            &BindGroupEntries::sequential((
+                post_process.source,
+                &screen_effect_pipeline.sampler,
+                settings_binding.clone(),
+            )),
+        );
+
+        let mut render_pass = render_context.begin_tracked_render_pass(RenderPassDescriptor {
+            label: Some("screen_effect_pass"),
+            color_attachments: &[Some(RenderPassColorAttachment {
+                view: post_process.destination,
+                depth_slice: None,
+                resolve_target: None,
+                ops: Operations::default(),
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        render_pass.set_render_pipeline(pipeline);
+        render_pass.set_bind_group(0, &bind_group, &[settings_index.index()]);
+        // A single triangle covering the whole screen.
+        render_pass.draw(0..3, 0..1);
+
+        Ok(())
+    }
+}
+
+#[derive(Resource)]
+struct ScreenEffectPipeline {
+    layout: BindGroupLayoutDescriptor,
+    sampler: Sampler,
+    pipeline_id: CachedRenderPipelineId,
+}
+
+fn init_screen_effect_pipeline(
+    mut commands: Commands,
+    render_device: Res<RenderDevice>,
+    asset_server: Res<AssetServer>,
+    fullscreen_shader: Res<FullscreenShader>,
+    pipeline_cache: Res<PipelineCache>,
+) {
+    let layout = BindGroupLayoutDescriptor::new(
+        "screen_effect_bind_group_layout",
+        &BindGroupLayoutEntries::sequential(
+            ShaderStages::FRAGMENT,
+            (
+                texture_2d(TextureSampleType::Float { filterable: true }),
+                sampler(SamplerBindingType::Filtering),
+                uniform_buffer::<ScreenEffect>(true),
+            ),
+        ),
+    );
+    let sampler = render_device.create_sampler(&SamplerDescriptor::default());
+    let pipeline_id = pipeline_cache.queue_render_pipeline(RenderPipelineDescriptor {
+        label: Some("screen_effect_pipeline".into()),
+        layout: vec![layout.clone()],
+        vertex: fullscreen_shader.to_vertex_state(),
+        fragment: Some(FragmentState {
+            shader: asset_server.load(SHADER_ASSET_PATH),
+            targets: vec![Some(ColorTargetState {
+                format: TextureFormat::bevy_default(),
+                blend: None,
+                write_mask: ColorWrites::ALL,
+            })],
+            ..default()
+        }),
+        ..default()
+    });
+    commands.insert_resource(ScreenEffectPipeline {
+        layout,
+        sampler,
+        pipeline_id,
+    });
+}
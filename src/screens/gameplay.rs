@@ -1,12 +1,37 @@
 //! The screen state for the main gameplay.
 
-use bevy::{input::common_conditions::input_just_pressed, prelude::*};
+use bevy::prelude::*;
 
-use crate::{Pause, demo::level::spawn_level, menus::Menu, screens::Screen};
+use crate::{
+    Pause,
+    demo::level::spawn_level,
+    input::{Action, action_just_pressed},
+    menus::Menu,
+    screens::Screen,
+    theme::navigation::menu_back_just_pressed,
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Screen::Gameplay), spawn_level);
 
+    // Toggle pause with the pause action.
+    app.add_systems(
+        Update,
+        (
+            (pause, spawn_pause_overlay, open_pause_menu).run_if(
+                in_state(Screen::Gameplay)
+                    .and(in_state(Menu::None))
+                    .and(action_just_pressed(Action::Pause)),
+            ),
+            // The pause menu closes itself on menu back, e.g. Escape.
+            close_menu.run_if(
+                in_state(Screen::Gameplay)
+                    .and(in_state(Menu::Pause))
+                    .and(action_just_pressed(Action::Pause))
+                    .and(not(menu_back_just_pressed)),
+            ),
+        ),
+    );
 
     app.add_systems(OnExit(Screen::Gameplay), (close_menu, unpause));
     app.add_systems(
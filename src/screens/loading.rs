@@ -3,25 +3,54 @@
 
 use bevy::prelude::*;
 
-use crate::{asset_tracking::ResourceHandles, screens::Screen, theme::prelude::*};
+use crate::{
+    asset_tracking::ResourceHandles,
+    screens::Screen,
+    theme::{palette::HEALTH_BAR_CHIP, prelude::*, widget::Meter},
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Screen::Loading), spawn_loading_screen);
 
     app.add_systems(
         Update,
-        enter_gameplay_screen.run_if(in_state(Screen::Loading).and(all_assets_loaded)),
+        (
+            update_loading_bar.run_if(in_state(Screen::Loading)),
+            enter_gameplay_screen.run_if(in_state(Screen::Loading).and(all_assets_loaded)),
+        ),
     );
 }
 
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct LoadingBar;
+
 fn spawn_loading_screen(mut commands: Commands) {
     commands.spawn((
         widget::ui_root("Loading Screen"),
         DespawnOnExit(Screen::Loading),
-        children![widget::label("Loading...")],
+        children![
+            widget::label("Loading..."),
+            (
+                LoadingBar,
+                widget::progress_bar("Loading Bar", px(400), HEALTH_BAR_CHIP),
+            ),
+        ],
     ));
 }
 
+fn update_loading_bar(
+    resource_handles: Res<ResourceHandles>,
+    mut bar_query: Query<&mut Meter, With<LoadingBar>>,
+) {
+    for mut meter in &mut bar_query {
+        let progress = resource_handles.progress();
+        if meter.fraction != progress {
+            meter.fraction = progress;
+        }
+    }
+}
+
 fn enter_gameplay_screen(mut next_screen: If<ResMut<NextState<Screen>>>) {
     next_screen.set(Screen::Gameplay);
 }
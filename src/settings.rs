@@ -0,0 +1,235 @@
+//! Player settings, kept between runs.
+//!
+//! The settings menu edits the [`Settings`] resource directly. Audio, camera
+//! and damage code read it where they need it, and the window options are
+//! applied here. Settings are saved with [`persistence`] when the menu closes.
+
+use bevy::{
+    prelude::*,
+    window::{PrimaryWindow, WindowMode},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{localization::Language, persistence, theme::palette::*};
+
+pub(super) fn plugin(app: &mut App) {
+    app.insert_resource(Settings::load());
+    app.add_systems(
+        Update,
+        (apply_window_settings, apply_game_speed).run_if(resource_changed::<Settings>),
+    );
+}
+
+#[derive(Resource, Reflect, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[reflect(Resource)]
+#[serde(default)]
+pub struct Settings {
+    /// Linear volume of all audio, between 0 and [`Settings::MAX_VOLUME`].
+    pub master_volume: f32,
+    /// Linear volume of [`Music`](crate::audio::Music), between 0 and 1.
+    pub music_volume: f32,
+    /// Linear volume of [`SoundEffect`](crate::audio::SoundEffect)s, between 0 and 1.
+    pub sfx_volume: f32,
+    pub fullscreen: bool,
+    /// Size of the window relative to [`Settings::BASE_RESOLUTION`] when not
+    /// fullscreen. Only used on native.
+    pub resolution_scale: f32,
+    /// Only used on native, browsers always sync to the display.
+    pub vsync: bool,
+    /// Scale the game view by whole numbers only, leaving wider letterbox bars
+    /// but keeping pixel art sharp.
+    pub pixel_perfect: bool,
+    /// How strongly the camera shakes, between 0 and 1.
+    pub screen_shake: f32,
+    pub difficulty: Difficulty,
+    /// The goose slowly gets hungry and starts starving without food.
+    pub hunger: bool,
+    pub language: Language,
+    pub theme: UiTheme,
+    /// Colors of health bars, picked to stay apart for colorblind players.
+    pub colorblind_mode: ColorblindMode,
+    /// Size of all UI text relative to the theme, between 0.8 and 1.4.
+    pub text_scale: f32,
+    /// Turns off screen shake, flashing and the curse's screen effects.
+    pub reduced_motion: bool,
+    /// Glide is switched on and off by presses instead of being held.
+    pub toggle_glide: bool,
+    /// Speed of gameplay relative to normal, between 0.5 and 1.
+    pub game_speed: f32,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            master_volume: 1.0,
+            music_volume: 1.0,
+            sfx_volume: 1.0,
+            fullscreen: false,
+            resolution_scale: 1.0,
+            vsync: true,
+            pixel_perfect: true,
+            screen_shake: 1.0,
+            difficulty: Difficulty::Normal,
+            hunger: true,
+            language: Language::English,
+            theme: UiTheme::Default,
+            colorblind_mode: ColorblindMode::Off,
+            text_scale: 1.0,
+            reduced_motion: false,
+            toggle_glide: false,
+            game_speed: 1.0,
+        }
+    }
+}
+
+impl Settings {
+    const SAVE_NAME: &str = "settings";
+
+    pub const MAX_VOLUME: f32 = 3.0;
+
+    /// Window size at a [`Settings::resolution_scale`] of 1.
+    pub const BASE_RESOLUTION: UVec2 = UVec2::new(1280, 720);
+
+    /// Choices offered for [`Settings::resolution_scale`].
+    pub const RESOLUTION_SCALES: [f32; 4] = [1.0, 1.25, 1.5, 2.0];
+
+    fn load() -> Self {
+        persistence::load(Self::SAVE_NAME).unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        persistence::save(Self::SAVE_NAME, self);
+    }
+
+    /// How strongly the camera shakes, taking reduced motion into account.
+    pub fn shake_strength(&self) -> f32 {
+        if self.reduced_motion {
+            0.0
+        } else {
+            self.screen_shake
+        }
+    }
+}
+
+/// How hard the game is, by how much damage the goose takes from enemies.
+#[derive(Reflect, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+}
+
+impl Difficulty {
+    pub const ALL: [Self; 3] = [Self::Easy, Self::Normal, Self::Hard];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Easy => "Easy",
+            Self::Normal => "Normal",
+            Self::Hard => "Hard",
+        }
+    }
+
+    /// Multiplier for damage dealt to the goose.
+    pub fn damage_taken(self) -> f32 {
+        match self {
+            Self::Easy => 0.5,
+            Self::Normal => 1.0,
+            Self::Hard => 1.5,
+        }
+    }
+}
+
+/// Which [`Theme`](crate::theme::style::Theme) the UI is styled with.
+#[derive(Reflect, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UiTheme {
+    #[default]
+    Default,
+    HighContrast,
+}
+
+impl UiTheme {
+    pub const ALL: [Self; 2] = [Self::Default, Self::HighContrast];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Default => "Default",
+            Self::HighContrast => "High Contrast",
+        }
+    }
+}
+
+/// Health bar colors for players who have trouble telling some colors apart.
+#[derive(Reflect, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColorblindMode {
+    #[default]
+    Off,
+    /// Protanopia and deuteranopia, where red and green look alike.
+    RedGreen,
+    /// Tritanopia, where blue and yellow look alike.
+    BlueYellow,
+}
+
+impl ColorblindMode {
+    pub const ALL: [Self; 3] = [Self::Off, Self::RedGreen, Self::BlueYellow];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Off => "Off",
+            Self::RedGreen => "Red-Green",
+            Self::BlueYellow => "Blue-Yellow",
+        }
+    }
+
+    /// Colors of the remaining health and of the chip of recently lost health.
+    pub fn health_bar_colors(self) -> (Color, Color) {
+        match self {
+            Self::Off => (HEALTH_BAR_FILL, HEALTH_BAR_CHIP),
+            Self::RedGreen => (HEALTH_BAR_FILL_RED_GREEN, HEALTH_BAR_CHIP_RED_GREEN),
+            Self::BlueYellow => (HEALTH_BAR_FILL_BLUE_YELLOW, HEALTH_BAR_CHIP_BLUE_YELLOW),
+        }
+    }
+}
+
+/// Apply the window options, but only when they change, so volume changes
+/// don't undo a window the player resized by hand.
+fn apply_window_settings(
+    settings: Res<Settings>,
+    mut applied: Local<Option<(bool, f32, bool)>>,
+    mut window: Single<&mut Window, With<PrimaryWindow>>,
+) {
+    let display = (
+        settings.fullscreen,
+        settings.resolution_scale,
+        settings.vsync,
+    );
+    if *applied == Some(display) {
+        return;
+    }
+    *applied = Some(display);
+
+    window.mode = if settings.fullscreen {
+        WindowMode::BorderlessFullscreen(MonitorSelection::Current)
+    } else {
+        WindowMode::Windowed
+    };
+    #[cfg(not(target_family = "wasm"))]
+    {
+        if !settings.fullscreen {
+            let size = Settings::BASE_RESOLUTION.as_vec2() * settings.resolution_scale;
+            window.resolution.set(size.x, size.y);
+        }
+        window.present_mode = if settings.vsync {
+            bevy::window::PresentMode::AutoVsync
+        } else {
+            bevy::window::PresentMode::AutoNoVsync
+        };
+    }
+}
+
+fn apply_game_speed(settings: Res<Settings>, mut time: ResMut<Time<Virtual>>) {
+    if time.relative_speed() != settings.game_speed {
+        time.set_relative_speed(settings.game_speed);
+    }
+}
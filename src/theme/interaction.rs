@@ -1,4 +1,7 @@
-use bevy::prelude::*;
+use bevy::{
+    input_focus::{InputFocus, InputFocusVisible},
+    prelude::*,
+};
 
 use crate::{asset_tracking::LoadResource, audio::sound_effect};
 
@@ -7,6 +10,7 @@ pub(super) fn plugin(app: &mut App) {
     app.add_observer(apply_interaction_palette_on_over);
     app.add_observer(apply_interaction_palette_on_out);
     app.add_observer(apply_interaction_palette_on_release);
+    app.add_systems(Update, apply_interaction_palette_on_focus);
 
     app.load_resource::<InteractionAssets>();
     app.add_observer(play_sound_effect_on_click);
@@ -16,12 +20,17 @@ pub(super) fn plugin(app: &mut App) {
 /// Palette for widget interactions. Add this to an entity that supports
 /// [`Interaction`]s, such as a button, to change its [`BackgroundColor`] based
 /// on the current interaction state.
+///
+/// While the entity has visible [`InputFocus`], e.g. after navigating to it
+/// with a gamepad, it is outlined in the `focused` color.
 #[derive(Component, Debug, Reflect)]
 #[reflect(Component)]
+#[require(Outline)]
 pub struct InteractionPalette {
     pub none: Color,
     pub hovered: Color,
     pub pressed: Color,
+    pub focused: Color,
 }
 
 fn apply_interaction_palette_on_click(
@@ -68,6 +77,23 @@ fn apply_interaction_palette_on_out(
     *bg = palette.none.into();
 }
 
+fn apply_interaction_palette_on_focus(
+    input_focus: Res<InputFocus>,
+    focus_visible: Res<InputFocusVisible>,
+    mut palette_query: Query<(Entity, &InteractionPalette, &mut Outline)>,
+) {
+    for (entity, palette, mut outline) in &mut palette_query {
+        let color = if focus_visible.0 && input_focus.0 == Some(entity) {
+            palette.focused
+        } else {
+            Color::NONE
+        };
+        if outline.color != color {
+            *outline = Outline::new(px(3), px(3), color);
+        }
+    }
+}
+
 #[derive(Resource, Asset, Clone, Reflect)]
 #[reflect(Resource)]
 struct InteractionAssets {
@@ -4,7 +4,9 @@
 #![allow(dead_code)]
 
 pub mod interaction;
+pub mod navigation;
 pub mod palette;
+pub mod style;
 pub mod widget;
 
 #[allow(unused_imports)]
@@ -14,6 +16,35 @@ pub mod prelude {
 
 use bevy::prelude::*;
 
+use crate::viewport::MainCamera;
+
 pub(super) fn plugin(app: &mut App) {
-    app.add_plugins(interaction::plugin);
+    app.add_plugins((
+        interaction::plugin,
+        navigation::plugin,
+        style::plugin,
+        widget::plugin,
+    ));
+    app.add_systems(Update, scale_ui_to_window);
+}
+
+/// Height of the game view the UI is laid out for.
+const REFERENCE_HEIGHT: f32 = 720.0;
+
+/// Scale the whole UI with the game view, so it keeps its layout at any size.
+fn scale_ui_to_window(
+    camera_query: Query<&Camera, With<MainCamera>>,
+    mut ui_scale: ResMut<UiScale>,
+) {
+    let Some(view_size) = camera_query
+        .single()
+        .ok()
+        .and_then(Camera::logical_viewport_size)
+    else {
+        return;
+    };
+    let scale = (view_size.y / REFERENCE_HEIGHT).clamp(0.5, 3.0);
+    if ui_scale.0 != scale {
+        ui_scale.0 = scale;
+    }
 }
@@ -0,0 +1,247 @@
+//! Moving focus between widgets without a mouse.
+//!
+//! The arrow keys and d-pad move [`InputFocus`] to the nearest
+//! [`widget::button`] in that direction, and Tab steps through buttons in
+//! layout order. Enter, Space or the South (A) button activates the focused
+//! button, triggering the same [`Activate`] event a click does. Hovering a
+//! button with the mouse focuses it too, so either way of navigating can pick
+//! up where the other left off. Left and right change the value of a focused
+//! [`Adjustable`] widget, like a slider, instead of moving focus.
+//!
+//! [`widget::button`]: crate::theme::widget::button
+
+use bevy::{
+    input_focus::{
+        InputDispatchPlugin, InputFocus, InputFocusVisible,
+        directional_navigation::DirectionalNavigationPlugin,
+    },
+    math::CompassOctant,
+    prelude::*,
+    ui::auto_directional_navigation::{AutoDirectionalNavigation, AutoDirectionalNavigator},
+};
+
+use crate::AppSystems;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_plugins((InputDispatchPlugin, DirectionalNavigationPlugin));
+    app.add_systems(
+        Update,
+        (navigate_with_direction, navigate_with_tab, activate_focused)
+            .chain()
+            .in_set(AppSystems::RecordInput),
+    );
+    app.add_observer(activate_on_click);
+    app.add_observer(focus_on_hover);
+}
+
+/// Triggered on a widget when it is clicked or activated from the keyboard or a gamepad.
+#[derive(EntityEvent, Debug, Clone)]
+pub struct Activate {
+    pub entity: Entity,
+}
+
+/// A focusable widget whose value changes with left and right instead of
+/// moving focus, receiving [`Adjust`] events.
+#[derive(Component, Reflect, Debug, Default)]
+#[reflect(Component)]
+pub struct Adjustable;
+
+/// Triggered on a focused [`Adjustable`] widget when the player presses left or right.
+#[derive(EntityEvent, Debug, Clone)]
+pub struct Adjust {
+    pub entity: Entity,
+    /// -1 for left, 1 for right.
+    pub direction: i32,
+}
+
+/// Run condition that is true when the player asks to leave the current menu.
+pub fn menu_back_just_pressed(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    gamepad_query: Query<&Gamepad>,
+) -> bool {
+    keyboard.just_pressed(KeyCode::Escape)
+        || gamepad_query
+            .iter()
+            .any(|gamepad| gamepad.just_pressed(GamepadButton::East))
+}
+
+fn activate_on_click(
+    click: On<Pointer<Click>>,
+    mut commands: Commands,
+    navigable_query: Query<(), With<AutoDirectionalNavigation>>,
+) {
+    let entity = click.event_target();
+    if navigable_query.contains(entity) {
+        commands.trigger(Activate { entity });
+    }
+}
+
+fn focus_on_hover(
+    over: On<Pointer<Over>>,
+    navigable_query: Query<(), With<AutoDirectionalNavigation>>,
+    mut input_focus: ResMut<InputFocus>,
+    mut focus_visible: ResMut<InputFocusVisible>,
+) {
+    let entity = over.event_target();
+    if navigable_query.contains(entity) {
+        input_focus.set(entity);
+        // The mouse cursor already shows where the player is pointing.
+        focus_visible.0 = false;
+    }
+}
+
+/// Directions that move focus, as keys and d-pad buttons.
+const DIRECTIONS: [(KeyCode, GamepadButton, CompassOctant); 4] = [
+    (
+        KeyCode::ArrowUp,
+        GamepadButton::DPadUp,
+        CompassOctant::North,
+    ),
+    (
+        KeyCode::ArrowDown,
+        GamepadButton::DPadDown,
+        CompassOctant::South,
+    ),
+    (
+        KeyCode::ArrowLeft,
+        GamepadButton::DPadLeft,
+        CompassOctant::West,
+    ),
+    (
+        KeyCode::ArrowRight,
+        GamepadButton::DPadRight,
+        CompassOctant::East,
+    ),
+];
+
+/// Visible navigable widgets from top to bottom, then left to right.
+fn layout_order(
+    navigable_query: &Query<
+        (Entity, &UiGlobalTransform, &InheritedVisibility),
+        With<AutoDirectionalNavigation>,
+    >,
+) -> Vec<Entity> {
+    let mut widgets: Vec<_> = navigable_query
+        .iter()
+        .filter(|(_, _, visibility)| visibility.get())
+        .map(|(entity, transform, _)| (entity, transform.translation))
+        .collect();
+    widgets.sort_by(|(_, a), (_, b)| a.y.total_cmp(&b.y).then(a.x.total_cmp(&b.x)));
+    widgets.into_iter().map(|(entity, _)| entity).collect()
+}
+
+fn navigate_with_direction(
+    mut commands: Commands,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    gamepad_query: Query<&Gamepad>,
+    mut navigator: AutoDirectionalNavigator,
+    navigable_query: Query<
+        (Entity, &UiGlobalTransform, &InheritedVisibility),
+        With<AutoDirectionalNavigation>,
+    >,
+    adjustable_query: Query<(), With<Adjustable>>,
+    mut focus_visible: ResMut<InputFocusVisible>,
+) {
+    let Some(direction) = DIRECTIONS.into_iter().find_map(|(key, button, direction)| {
+        let pressed = keyboard.just_pressed(key)
+            || gamepad_query
+                .iter()
+                .any(|gamepad| gamepad.just_pressed(button));
+        pressed.then_some(direction)
+    }) else {
+        return;
+    };
+    focus_visible.0 = true;
+
+    let focus_is_navigable = navigator
+        .input_focus()
+        .is_some_and(|entity| navigable_query.contains(entity));
+    if focus_is_navigable {
+        if let Some(entity) = navigator
+            .input_focus()
+            .filter(|&entity| adjustable_query.contains(entity))
+        {
+            let step = match direction {
+                CompassOctant::West => -1,
+                CompassOctant::East => 1,
+                _ => 0,
+            };
+            if step != 0 {
+                commands.trigger(Adjust {
+                    entity,
+                    direction: step,
+                });
+                return;
+            }
+        }
+        // Staying put at the edge of a menu is fine.
+        let _ = navigator.navigate(direction);
+        return;
+    }
+
+    // Nothing in the current menu is focused yet, so start at the top.
+    if let Some(&first) = layout_order(&navigable_query).first() {
+        navigator.manual_directional_navigation.focus.set(first);
+    }
+}
+
+/// Tab moves focus to the next widget in layout order, Shift+Tab to the previous.
+fn navigate_with_tab(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    navigable_query: Query<
+        (Entity, &UiGlobalTransform, &InheritedVisibility),
+        With<AutoDirectionalNavigation>,
+    >,
+    mut input_focus: ResMut<InputFocus>,
+    mut focus_visible: ResMut<InputFocusVisible>,
+) {
+    if !keyboard.just_pressed(KeyCode::Tab) {
+        return;
+    }
+    let order = layout_order(&navigable_query);
+    if order.is_empty() {
+        return;
+    }
+    focus_visible.0 = true;
+
+    let backwards = keyboard.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    let current = input_focus
+        .0
+        .and_then(|focus| order.iter().position(|&entity| entity == focus));
+    let next = match (current, backwards) {
+        (Some(index), false) => (index + 1) % order.len(),
+        (Some(index), true) => (index + order.len() - 1) % order.len(),
+        (None, false) => 0,
+        (None, true) => order.len() - 1,
+    };
+    input_focus.set(order[next]);
+}
+
+fn activate_focused(
+    mut commands: Commands,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    gamepad_query: Query<&Gamepad>,
+    input_focus: Res<InputFocus>,
+    focus_visible: Res<InputFocusVisible>,
+    navigable_query: Query<&InheritedVisibility, With<AutoDirectionalNavigation>>,
+) {
+    let key_pressed =
+        keyboard.any_just_pressed([KeyCode::Enter, KeyCode::NumpadEnter, KeyCode::Space]);
+    let button_pressed = gamepad_query
+        .iter()
+        .any(|gamepad| gamepad.just_pressed(GamepadButton::South));
+    // Keys only activate a focus the player can see, not whatever the mouse
+    // happens to rest on.
+    if !(button_pressed || key_pressed && focus_visible.0) {
+        return;
+    }
+    let Some(entity) = input_focus.0 else {
+        return;
+    };
+    if navigable_query
+        .get(entity)
+        .is_ok_and(|visibility| visibility.get())
+    {
+        commands.trigger(Activate { entity });
+    }
+}
@@ -1,16 +1,23 @@
 use bevy::prelude::*;
 
-/// #ddd369
-pub const LABEL_TEXT: Color = Color::srgb(0.867, 0.827, 0.412);
+/// #1e1a14
+pub const HEALTH_BAR_BACKGROUND: Color = Color::srgb(0.118, 0.102, 0.078);
+/// #d93a3a
+pub const HEALTH_BAR_FILL: Color = Color::srgb(0.851, 0.227, 0.227);
+/// #f2d88c
+pub const HEALTH_BAR_CHIP: Color = Color::srgb(0.949, 0.847, 0.549);
+/// #0072b2
+pub const HEALTH_BAR_FILL_RED_GREEN: Color = Color::srgb(0.0, 0.447, 0.698);
+/// #e69f00
+pub const HEALTH_BAR_CHIP_RED_GREEN: Color = Color::srgb(0.902, 0.624, 0.0);
+/// #cc3311
+pub const HEALTH_BAR_FILL_BLUE_YELLOW: Color = Color::srgb(0.800, 0.200, 0.067);
+/// #eeeeee
+pub const HEALTH_BAR_CHIP_BLUE_YELLOW: Color = Color::srgb(0.933, 0.933, 0.933);
 
-/// #fcfbcc
-pub const HEADER_TEXT: Color = Color::srgb(0.988, 0.984, 0.800);
-
-/// #ececec
-pub const BUTTON_TEXT: Color = Color::srgb(0.925, 0.925, 0.925);
-/// #4666bf
-pub const BUTTON_BACKGROUND: Color = Color::srgb(0.275, 0.400, 0.750);
-/// #6299d1
-pub const BUTTON_HOVERED_BACKGROUND: Color = Color::srgb(0.384, 0.600, 0.820);
-/// #3d4999
-pub const BUTTON_PRESSED_BACKGROUND: Color = Color::srgb(0.239, 0.286, 0.600);
+/// #8e3bd1
+pub const CURSE_METER_FILL: Color = Color::srgb(0.557, 0.231, 0.820);
+/// #6b5a7a
+pub const CURSE_PIP: Color = Color::srgb(0.420, 0.353, 0.478);
+/// #c58cf2
+pub const CURSE_PIP_REACHED: Color = Color::srgb(0.773, 0.549, 0.949);
@@ -0,0 +1,328 @@
+//! UI themes authored as assets.
+//!
+//! A [`Theme`] (`assets/themes/*.theme.ron`) holds the colors, font, font
+//! sizes, spacing, button sizes and interaction palettes of the UI. Widgets
+//! spawn with a [`ThemedText`] or [`ThemedNode`] role and are styled from the
+//! theme picked in the settings as soon as it has loaded, with font sizes
+//! scaled by [`Settings::text_scale`]. Changing either, or editing the
+//! theme's file in a native dev build, restyles every widget on screen.
+
+use bevy::{
+    asset::{AssetLoader, LoadContext, io::Reader},
+    prelude::*,
+};
+use serde::{Deserialize, Deserializer};
+
+use crate::{
+    asset_tracking::LoadResource,
+    settings::{Settings, UiTheme},
+    theme::interaction::InteractionPalette,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_asset::<Theme>();
+    app.register_asset_loader(ThemeLoader);
+    app.load_resource::<ThemeAssets>();
+    app.add_systems(PostUpdate, apply_theme.before(bevy::ui::UiSystems::Layout));
+}
+
+#[derive(Asset, Reflect, Debug, Deserialize)]
+pub struct Theme {
+    pub colors: ThemeColors,
+    /// Path of the font used for all UI text, or Bevy's default font if unset.
+    #[serde(default)]
+    pub font: Option<String>,
+    #[serde(skip)]
+    font_handle: Handle<Font>,
+    pub font_sizes: FontSizes,
+    pub spacing: Spacing,
+    pub button_sizes: ButtonSizes,
+    /// Palette of buttons, toggles and selectors.
+    pub button_palette: ThemePalette,
+    /// Palette of sliders and text inputs.
+    pub field_palette: ThemePalette,
+}
+
+#[derive(Reflect, Debug, Deserialize)]
+pub struct ThemeColors {
+    #[serde(deserialize_with = "hex_color")]
+    pub header_text: Color,
+    #[serde(deserialize_with = "hex_color")]
+    pub label_text: Color,
+    #[serde(deserialize_with = "hex_color")]
+    pub button_text: Color,
+    #[serde(deserialize_with = "hex_color")]
+    pub meter_background: Color,
+    #[serde(deserialize_with = "hex_color")]
+    pub meter_border: Color,
+    #[serde(deserialize_with = "hex_color")]
+    pub slider_fill: Color,
+}
+
+#[derive(Reflect, Debug, Deserialize)]
+pub struct FontSizes {
+    pub header: f32,
+    pub label: f32,
+    pub small_label: f32,
+    pub button: f32,
+    pub button_medium: f32,
+    pub button_small: f32,
+    /// Text of value widgets like sliders and toggles.
+    pub value: f32,
+}
+
+#[derive(Reflect, Debug, Deserialize)]
+pub struct Spacing {
+    /// Gap between the widgets of a [`ui_root`](super::widget::ui_root).
+    pub root_gap: f32,
+    pub grid_row_gap: f32,
+    pub grid_column_gap: f32,
+}
+
+#[derive(Reflect, Debug, Deserialize)]
+pub struct ButtonSizes {
+    pub button: Vec2,
+    pub button_medium: Vec2,
+    pub button_small: Vec2,
+    /// Size of value widgets, so they line up in a settings grid.
+    pub value: Vec2,
+}
+
+/// Colors an [`InteractionPalette`] is built from.
+#[derive(Reflect, Debug, Deserialize)]
+pub struct ThemePalette {
+    #[serde(deserialize_with = "hex_color")]
+    pub none: Color,
+    #[serde(deserialize_with = "hex_color")]
+    pub hovered: Color,
+    #[serde(deserialize_with = "hex_color")]
+    pub pressed: Color,
+    #[serde(deserialize_with = "hex_color")]
+    pub focused: Color,
+}
+
+impl ThemePalette {
+    fn interaction_palette(&self) -> InteractionPalette {
+        InteractionPalette {
+            none: self.none,
+            hovered: self.hovered,
+            pressed: self.pressed,
+            focused: self.focused,
+        }
+    }
+}
+
+/// Read a color written as a hex string like `"#4666bf"`.
+fn hex_color<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Color, D::Error> {
+    let hex = String::deserialize(deserializer)?;
+    Srgba::hex(&hex)
+        .map(Color::from)
+        .map_err(serde::de::Error::custom)
+}
+
+#[derive(TypePath, Default)]
+struct ThemeLoader;
+
+impl AssetLoader for ThemeLoader {
+    type Asset = Theme;
+    type Settings = ();
+    type Error = BevyError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let mut theme: Theme = ron::de::from_bytes(&bytes)?;
+        if let Some(font) = &theme.font {
+            theme.font_handle = load_context.load(font.clone());
+        }
+        Ok(theme)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["theme.ron"]
+    }
+}
+
+#[derive(Resource, Asset, Clone, Reflect)]
+#[reflect(Resource)]
+pub struct ThemeAssets {
+    #[dependency]
+    default: Handle<Theme>,
+    #[dependency]
+    high_contrast: Handle<Theme>,
+}
+
+impl ThemeAssets {
+    pub fn get(&self, theme: UiTheme) -> &Handle<Theme> {
+        match theme {
+            UiTheme::Default => &self.default,
+            UiTheme::HighContrast => &self.high_contrast,
+        }
+    }
+}
+
+impl FromWorld for ThemeAssets {
+    fn from_world(world: &mut World) -> Self {
+        let assets = world.resource::<AssetServer>();
+        Self {
+            default: assets.load("themes/default.theme.ron"),
+            high_contrast: assets.load("themes/high_contrast.theme.ron"),
+        }
+    }
+}
+
+/// Size of a button, picking its node size and font size from the [`Theme`].
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ButtonSize {
+    Large,
+    Medium,
+    Small,
+    /// A value widget like a toggle or selector.
+    Value,
+}
+
+impl ButtonSize {
+    fn size(self, theme: &Theme) -> Vec2 {
+        let sizes = &theme.button_sizes;
+        match self {
+            Self::Large => sizes.button,
+            Self::Medium => sizes.button_medium,
+            Self::Small => sizes.button_small,
+            Self::Value => sizes.value,
+        }
+    }
+
+    fn font_size(self, theme: &Theme) -> f32 {
+        let sizes = &theme.font_sizes;
+        match self {
+            Self::Large => sizes.button,
+            Self::Medium => sizes.button_medium,
+            Self::Small => sizes.button_small,
+            Self::Value => sizes.value,
+        }
+    }
+}
+
+/// Text styled by the current [`Theme`].
+#[derive(Component, Reflect, Debug, Clone, Copy, PartialEq, Eq)]
+#[reflect(Component)]
+pub enum ThemedText {
+    Header,
+    Label,
+    SmallLabel,
+    Button(ButtonSize),
+    /// The percentage on a progress bar.
+    Meter,
+}
+
+/// A UI node sized and colored by the current [`Theme`].
+#[derive(Component, Reflect, Debug, Clone, Copy, PartialEq, Eq)]
+#[reflect(Component)]
+pub enum ThemedNode {
+    Root,
+    Grid,
+    Button(ButtonSize),
+    /// A slider track or text input.
+    Field,
+    Meter,
+    SliderFill,
+    /// A thin highlight, like a buff's time left or a boss phase marker.
+    Marker,
+}
+
+/// Restyle new widgets, or all of them when the theme changes or is edited.
+fn apply_theme(
+    mut commands: Commands,
+    mut theme_events: MessageReader<AssetEvent<Theme>>,
+    theme_assets: Option<Res<ThemeAssets>>,
+    themes: Res<Assets<Theme>>,
+    settings: Res<Settings>,
+    mut applied: Local<Option<(UiTheme, f32)>>,
+    mut text_query: Query<(Ref<ThemedText>, &mut TextFont, &mut TextColor)>,
+    mut node_query: Query<(
+        Entity,
+        Ref<ThemedNode>,
+        &mut Node,
+        Option<&mut BackgroundColor>,
+        Option<&mut BorderColor>,
+    )>,
+) {
+    let edited = theme_events.read().count() > 0;
+    let Some(theme) = theme_assets
+        .as_ref()
+        .and_then(|assets| themes.get(assets.get(settings.theme)))
+    else {
+        return;
+    };
+    let style = (settings.theme, settings.text_scale);
+    let restyle_all = edited || *applied != Some(style);
+    *applied = Some(style);
+
+    for (role, mut font, mut color) in &mut text_query {
+        if !restyle_all && !role.is_added() {
+            continue;
+        }
+        let (font_size, text_color) = match *role {
+            ThemedText::Header => (theme.font_sizes.header, theme.colors.header_text),
+            ThemedText::Label => (theme.font_sizes.label, theme.colors.label_text),
+            ThemedText::SmallLabel => (theme.font_sizes.small_label, theme.colors.label_text),
+            ThemedText::Button(size) => (size.font_size(theme), theme.colors.button_text),
+            ThemedText::Meter => (theme.font_sizes.small_label, theme.colors.button_text),
+        };
+        font.font = theme.font_handle.clone();
+        font.font_size = font_size * settings.text_scale;
+        color.0 = text_color;
+    }
+
+    for (entity, role, mut node, background, border) in &mut node_query {
+        if !restyle_all && !role.is_added() {
+            continue;
+        }
+        let (size, palette, background_color) = match *role {
+            ThemedNode::Root => {
+                node.row_gap = px(theme.spacing.root_gap);
+                continue;
+            }
+            ThemedNode::Grid => {
+                node.row_gap = px(theme.spacing.grid_row_gap);
+                node.column_gap = px(theme.spacing.grid_column_gap);
+                continue;
+            }
+            ThemedNode::Button(size) => (
+                Some(size.size(theme)),
+                Some(&theme.button_palette),
+                theme.button_palette.none,
+            ),
+            ThemedNode::Field => (
+                Some(theme.button_sizes.value),
+                Some(&theme.field_palette),
+                theme.field_palette.none,
+            ),
+            ThemedNode::Meter => (None, None, theme.colors.meter_background),
+            ThemedNode::SliderFill => (None, None, theme.colors.slider_fill),
+            ThemedNode::Marker => (None, None, theme.colors.label_text),
+        };
+        if let Some(size) = size {
+            node.width = px(size.x);
+            node.height = px(size.y);
+        }
+        if let Some(palette) = palette {
+            commands
+                .entity(entity)
+                .insert(palette.interaction_palette());
+        }
+        if let Some(mut background) = background {
+            background.0 = background_color;
+        }
+        if *role == ThemedNode::Meter
+            && let Some(mut border) = border
+        {
+            *border = BorderColor::all(theme.colors.meter_border);
+        }
+    }
+}
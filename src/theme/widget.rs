@@ -4,15 +4,61 @@ use std::borrow::Cow;
 
 use bevy::{
     ecs::{spawn::SpawnWith, system::IntoObserverSystem},
+    input::{
+        ButtonState,
+        keyboard::{Key, KeyboardInput},
+        mouse::MouseScrollUnit,
+    },
+    input_focus::InputFocus,
     prelude::*,
+    ui::{UiSystems, auto_directional_navigation::AutoDirectionalNavigation},
 };
 
-use crate::theme::{interaction::InteractionPalette, palette::*};
+use crate::{
+    localization::{Localization, Localized},
+    theme::{
+        navigation::{Activate, Adjust, Adjustable},
+        style::{ButtonSize, ThemedNode, ThemedText},
+    },
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        (type_into_text_inputs, scroll_with_input, scroll_to_focus),
+    );
+    app.add_systems(
+        PostUpdate,
+        (
+            (update_sliders, update_meters).chain(),
+            update_toggles,
+            update_selectors,
+            update_text_inputs,
+        )
+            .before(UiSystems::Layout),
+    );
+    app.add_observer(press_slider);
+    app.add_observer(drag_slider);
+    app.add_observer(adjust_slider);
+    app.add_observer(activate_toggle);
+    app.add_observer(activate_selector);
+    app.add_observer(adjust_selector);
+    app.add_observer(scroll_on_wheel);
+}
+
+/// Triggered on a [`slider`], [`toggle`], [`selector`] or [`text_input`] when
+/// the player changes its value.
+#[derive(EntityEvent, Debug, Clone)]
+pub struct ValueChanged<T: Clone + Send + Sync + 'static> {
+    pub entity: Entity,
+    pub value: T,
+}
 
 /// A root UI node that fills the window and centers its content.
 pub fn ui_root(name: impl Into<Cow<'static, str>>) -> impl Bundle {
     (
         Name::new(name),
+        ThemedNode::Root,
         Node {
             position_type: PositionType::Absolute,
             width: percent(100),
@@ -30,24 +76,138 @@ pub fn ui_root(name: impl Into<Cow<'static, str>>) -> impl Bundle {
 
 /// A simple header label. Bigger than [`label`].
 pub fn header(text: impl Into<String>) -> impl Bundle {
+    let text = text.into();
     (
         Name::new("Header"),
-        Text(text.into()),
-        TextFont::from_font_size(40.0),
-        TextColor(HEADER_TEXT),
+        Localized::new(text.clone()),
+        Text(text),
+        ThemedText::Header,
     )
 }
 
 /// A simple text label.
 pub fn label(text: impl Into<String>) -> impl Bundle {
+    let text = text.into();
     (
         Name::new("Label"),
-        Text(text.into()),
-        TextFont::from_font_size(24.0),
-        TextColor(LABEL_TEXT),
+        Localized::new(text.clone()),
+        Text(text),
+        ThemedText::Label,
     )
 }
 
+/// A small text label for overlays like the HUD.
+pub fn small_label(text: impl Into<String>) -> impl Bundle {
+    let text = text.into();
+    (
+        Name::new("Small Label"),
+        Localized::new(text.clone()),
+        Text(text),
+        ThemedText::SmallLabel,
+    )
+}
+
+/// A horizontal bar filled up to [`Meter::fraction`], e.g. for health or stamina.
+pub fn meter(
+    name: impl Into<Cow<'static, str>>,
+    width: Val,
+    height: Val,
+    fill: Color,
+) -> impl Bundle {
+    (
+        Name::new(name),
+        Meter { fraction: 1.0 },
+        ThemedNode::Meter,
+        Node {
+            width,
+            height,
+            border: UiRect::all(px(2)),
+            ..default()
+        },
+        children![(
+            Name::new("Meter Fill"),
+            MeterFill,
+            Node {
+                width: percent(100),
+                height: percent(100),
+                ..default()
+            },
+            BackgroundColor(fill),
+        )],
+    )
+}
+
+/// How full a [`meter`] is, between 0 and 1.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct Meter {
+    pub fraction: f32,
+}
+
+/// The filled part of a [`meter`].
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct MeterFill;
+
+/// Text showing how full a [`progress_bar`] is.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct MeterText;
+
+/// A [`meter`] labelled with how full it is as a percentage, e.g. for loading.
+pub fn progress_bar(name: impl Into<Cow<'static, str>>, width: Val, fill: Color) -> impl Bundle {
+    (
+        Name::new(name),
+        Meter { fraction: 0.0 },
+        ThemedNode::Meter,
+        Node {
+            width,
+            height: px(28),
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
+            border: UiRect::all(px(2)),
+            ..default()
+        },
+        children![
+            (
+                Name::new("Progress Bar Fill"),
+                MeterFill,
+                Node {
+                    position_type: PositionType::Absolute,
+                    left: px(0),
+                    height: percent(100),
+                    ..default()
+                },
+                BackgroundColor(fill),
+            ),
+            (
+                Name::new("Progress Bar Text"),
+                MeterText,
+                Text::default(),
+                ThemedText::Meter,
+            ),
+        ],
+    )
+}
+
+fn update_meters(
+    meter_query: Query<(&Meter, &Children), Changed<Meter>>,
+    mut fill_query: Query<&mut Node, With<MeterFill>>,
+    mut text_query: Query<&mut Text, With<MeterText>>,
+) {
+    for (meter, children) in &meter_query {
+        let fraction = meter.fraction.clamp(0.0, 1.0);
+        let mut fills = fill_query.iter_many_mut(children);
+        while let Some(mut node) = fills.fetch_next() {
+            node.width = percent(fraction * 100.0);
+        }
+        let mut texts = text_query.iter_many_mut(children);
+        while let Some(mut text) = texts.fetch_next() {
+            text.0 = format!("{:.0}%", fraction * 100.0);
+        }
+    }
+}
+
 /// A large rounded button with text and an action defined as an [`Observer`].
 pub fn button<E, B, M, I>(text: impl Into<String>, action: I) -> impl Bundle
 where
@@ -58,6 +218,7 @@ where
     button_base(
         text,
         action,
+        ButtonSize::Large,
         Node {
             width: px(380),
             height: px(80),
@@ -69,6 +230,28 @@ where
     )
 }
 
+/// A button sized to fit in a row of a settings grid.
+pub fn button_medium<E, B, M, I>(text: impl Into<String>, action: I) -> impl Bundle
+where
+    E: EntityEvent,
+    B: Bundle,
+    I: IntoObserverSystem<E, B, M>,
+{
+    button_base(
+        text,
+        action,
+        ButtonSize::Medium,
+        Node {
+            width: px(280),
+            height: px(40),
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
+            border_radius: BorderRadius::all(px(8)),
+            ..default()
+        },
+    )
+}
+
 /// A small square button with text and an action defined as an [`Observer`].
 pub fn button_small<E, B, M, I>(text: impl Into<String>, action: I) -> impl Bundle
 where
@@ -79,6 +262,7 @@ where
     button_base(
         text,
         action,
+        ButtonSize::Small,
         Node {
             width: px(30),
             height: px(30),
@@ -93,6 +277,7 @@ where
 fn button_base<E, B, M, I>(
     text: impl Into<String>,
     action: I,
+    size: ButtonSize,
     button_bundle: impl Bundle,
 ) -> impl Bundle
 where
@@ -105,22 +290,18 @@ where
     (
         Name::new("Button"),
         Node::default(),
-        Children::spawn(SpawnWith(|parent: &mut ChildSpawner| {
+        Children::spawn(SpawnWith(move |parent: &mut ChildSpawner| {
             parent
                 .spawn((
                     Name::new("Button Inner"),
                     Button,
-                    BackgroundColor(BUTTON_BACKGROUND),
-                    InteractionPalette {
-                        none: BUTTON_BACKGROUND,
-                        hovered: BUTTON_HOVERED_BACKGROUND,
-                        pressed: BUTTON_PRESSED_BACKGROUND,
-                    },
+                    ThemedNode::Button(size),
+                    AutoDirectionalNavigation::default(),
                     children![(
                         Name::new("Button Text"),
+                        Localized::new(text.clone()),
                         Text(text),
-                        TextFont::from_font_size(40.0),
-                        TextColor(BUTTON_TEXT),
+                        ThemedText::Button(size),
                         // Don't bubble picking events from the text up to the button.
                         Pickable::IGNORE,
                     )],
@@ -130,3 +311,556 @@ where
         })),
     )
 }
+
+/// Size shared by the value widgets, so they line up in a settings grid.
+fn value_widget_node() -> Node {
+    Node {
+        width: px(240),
+        height: px(36),
+        align_items: AlignItems::Center,
+        justify_content: JustifyContent::Center,
+        border_radius: BorderRadius::all(px(8)),
+        ..default()
+    }
+}
+
+/// A bar that can be dragged, clicked or adjusted with left and right to pick
+/// a value between `min` and `max`, shown as a percentage.
+pub fn slider<B, M, I>(value: f32, min: f32, max: f32, step: f32, action: I) -> impl Bundle
+where
+    B: Bundle,
+    I: IntoObserverSystem<ValueChanged<f32>, B, M>,
+{
+    let action = IntoObserverSystem::into_system(action);
+    (
+        Name::new("Slider"),
+        Node::default(),
+        Children::spawn(SpawnWith(move |parent: &mut ChildSpawner| {
+            parent
+                .spawn((
+                    Name::new("Slider Track"),
+                    Slider {
+                        value,
+                        min,
+                        max,
+                        step,
+                    },
+                    Meter { fraction: 0.0 },
+                    Adjustable,
+                    AutoDirectionalNavigation::default(),
+                    ThemedNode::Field,
+                    value_widget_node(),
+                    children![
+                        (
+                            Name::new("Slider Fill"),
+                            MeterFill,
+                            ThemedNode::SliderFill,
+                            Node {
+                                position_type: PositionType::Absolute,
+                                left: px(0),
+                                height: percent(100),
+                                border_radius: BorderRadius::all(px(8)),
+                                ..default()
+                            },
+                            Pickable::IGNORE,
+                        ),
+                        (
+                            Name::new("Slider Text"),
+                            Text::default(),
+                            ThemedText::Button(ButtonSize::Value),
+                            Pickable::IGNORE,
+                        ),
+                    ],
+                ))
+                .observe(action);
+        })),
+    )
+}
+
+/// The value of a [`slider`].
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct Slider {
+    pub value: f32,
+    pub min: f32,
+    pub max: f32,
+    /// How much one press of left or right changes the value.
+    pub step: f32,
+}
+
+impl Slider {
+    fn fraction(&self) -> f32 {
+        (self.value - self.min) / (self.max - self.min)
+    }
+
+    /// Set the value, returning whether it changed.
+    fn set(&mut self, value: f32) -> bool {
+        let value = value.clamp(self.min, self.max);
+        let changed = value != self.value;
+        self.value = value;
+        changed
+    }
+}
+
+fn press_slider(
+    press: On<Pointer<Press>>,
+    mut commands: Commands,
+    mut slider_query: Query<&mut Slider>,
+) {
+    let entity = press.event_target();
+    let Ok(mut slider) = slider_query.get_mut(entity) else {
+        return;
+    };
+    // UI picking reports hits from -0.5 on the left edge to 0.5 on the right.
+    let Some(position) = press.hit.position else {
+        return;
+    };
+    let value = slider.min + (position.x + 0.5) * (slider.max - slider.min);
+    if slider.set(value) {
+        commands.trigger(ValueChanged {
+            entity,
+            value: slider.value,
+        });
+    }
+}
+
+fn drag_slider(
+    drag: On<Pointer<Drag>>,
+    mut commands: Commands,
+    mut slider_query: Query<(&mut Slider, &ComputedNode)>,
+) {
+    let entity = drag.event_target();
+    let Ok((mut slider, computed)) = slider_query.get_mut(entity) else {
+        return;
+    };
+    let width = computed.size().x * computed.inverse_scale_factor();
+    if width <= 0.0 {
+        return;
+    }
+    let value = slider.value + drag.delta.x / width * (slider.max - slider.min);
+    if slider.set(value) {
+        commands.trigger(ValueChanged {
+            entity,
+            value: slider.value,
+        });
+    }
+}
+
+fn adjust_slider(adjust: On<Adjust>, mut commands: Commands, mut slider_query: Query<&mut Slider>) {
+    let Ok(mut slider) = slider_query.get_mut(adjust.entity) else {
+        return;
+    };
+    let value = slider.value + slider.step * adjust.direction as f32;
+    if slider.set(value) {
+        commands.trigger(ValueChanged {
+            entity: adjust.entity,
+            value: slider.value,
+        });
+    }
+}
+
+fn update_sliders(
+    mut slider_query: Query<(&Slider, &mut Meter, &Children), Changed<Slider>>,
+    mut text_query: Query<&mut Text>,
+) {
+    for (slider, mut meter, children) in &mut slider_query {
+        meter.fraction = slider.fraction();
+        let mut texts = text_query.iter_many_mut(children);
+        while let Some(mut text) = texts.fetch_next() {
+            text.0 = format!("{:.0}%", slider.value * 100.0);
+        }
+    }
+}
+
+/// A button switching between on and off.
+pub fn toggle<B, M, I>(on: bool, action: I) -> impl Bundle
+where
+    B: Bundle,
+    I: IntoObserverSystem<ValueChanged<bool>, B, M>,
+{
+    // The label is filled in by `update_toggles`, in the player's language.
+    button_base(
+        "",
+        action,
+        ButtonSize::Value,
+        (Toggle { on }, value_widget_node()),
+    )
+}
+
+/// The state of a [`toggle`].
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct Toggle {
+    pub on: bool,
+}
+
+impl Toggle {
+    fn label(on: bool) -> &'static str {
+        if on { "On" } else { "Off" }
+    }
+}
+
+fn activate_toggle(
+    activate: On<Activate>,
+    mut commands: Commands,
+    mut toggle_query: Query<&mut Toggle>,
+) {
+    let Ok(mut toggle) = toggle_query.get_mut(activate.entity) else {
+        return;
+    };
+    toggle.on = !toggle.on;
+    commands.trigger(ValueChanged {
+        entity: activate.entity,
+        value: toggle.on,
+    });
+}
+
+fn update_toggles(
+    localization: Localization,
+    toggle_query: Query<(Ref<Toggle>, &Children)>,
+    mut text_query: Query<&mut Text>,
+) {
+    for (toggle, children) in &toggle_query {
+        if !toggle.is_changed() && !localization.is_changed() {
+            continue;
+        }
+        let mut texts = text_query.iter_many_mut(children);
+        while let Some(mut text) = texts.fetch_next() {
+            text.0 = localization.get(Toggle::label(toggle.on));
+        }
+    }
+}
+
+/// A button cycling through a list of options when activated or adjusted with
+/// left and right, like a compact dropdown.
+pub fn selector<B, M, I>(options: Vec<String>, selected: usize, action: I) -> impl Bundle
+where
+    B: Bundle,
+    I: IntoObserverSystem<ValueChanged<usize>, B, M>,
+{
+    let selector = Selector { options, selected };
+    // The label is filled in by `update_selectors`, in the player's language.
+    button_base(
+        "",
+        action,
+        ButtonSize::Value,
+        (selector, Adjustable, value_widget_node()),
+    )
+}
+
+/// The options and current choice of a [`selector`].
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct Selector {
+    pub options: Vec<String>,
+    pub selected: usize,
+}
+
+impl Selector {
+    fn label(&self, localization: &Localization) -> String {
+        let option = self.options.get(self.selected).map_or("", String::as_str);
+        format!("< {} >", localization.get(option))
+    }
+
+    /// Move the selection by `steps`, wrapping around at either end.
+    fn cycle(&mut self, steps: i32) {
+        let len = self.options.len() as i32;
+        if len > 0 {
+            self.selected = (self.selected as i32 + steps).rem_euclid(len) as usize;
+        }
+    }
+}
+
+fn activate_selector(
+    activate: On<Activate>,
+    mut commands: Commands,
+    mut selector_query: Query<&mut Selector>,
+) {
+    let Ok(mut selector) = selector_query.get_mut(activate.entity) else {
+        return;
+    };
+    selector.cycle(1);
+    commands.trigger(ValueChanged {
+        entity: activate.entity,
+        value: selector.selected,
+    });
+}
+
+fn adjust_selector(
+    adjust: On<Adjust>,
+    mut commands: Commands,
+    mut selector_query: Query<&mut Selector>,
+) {
+    let Ok(mut selector) = selector_query.get_mut(adjust.entity) else {
+        return;
+    };
+    selector.cycle(adjust.direction);
+    commands.trigger(ValueChanged {
+        entity: adjust.entity,
+        value: selector.selected,
+    });
+}
+
+fn update_selectors(
+    localization: Localization,
+    selector_query: Query<(Ref<Selector>, &Children)>,
+    mut text_query: Query<&mut Text>,
+) {
+    for (selector, children) in &selector_query {
+        if !selector.is_changed() && !localization.is_changed() {
+            continue;
+        }
+        let mut texts = text_query.iter_many_mut(children);
+        while let Some(mut text) = texts.fetch_next() {
+            text.0 = selector.label(&localization);
+        }
+    }
+}
+
+/// A single line of text the player can type into while it has focus.
+pub fn text_input<B, M, I>(value: impl Into<String>, max_length: usize, action: I) -> impl Bundle
+where
+    B: Bundle,
+    I: IntoObserverSystem<ValueChanged<String>, B, M>,
+{
+    let value = value.into();
+    let action = IntoObserverSystem::into_system(action);
+    (
+        Name::new("Text Input"),
+        Node::default(),
+        Children::spawn(SpawnWith(move |parent: &mut ChildSpawner| {
+            parent
+                .spawn((
+                    Name::new("Text Input Field"),
+                    TextInput { value, max_length },
+                    // Left and right shouldn't leave the field while typing.
+                    Adjustable,
+                    AutoDirectionalNavigation::default(),
+                    ThemedNode::Field,
+                    Node {
+                        justify_content: JustifyContent::Start,
+                        padding: UiRect::horizontal(px(10)),
+                        ..value_widget_node()
+                    },
+                    children![(
+                        Name::new("Text Input Text"),
+                        Text::default(),
+                        ThemedText::Button(ButtonSize::Value),
+                        Pickable::IGNORE,
+                    )],
+                ))
+                .observe(action);
+        })),
+    )
+}
+
+/// The contents of a [`text_input`].
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct TextInput {
+    pub value: String,
+    /// Longest value in characters the player can type.
+    pub max_length: usize,
+}
+
+fn type_into_text_inputs(
+    mut commands: Commands,
+    mut keyboard_inputs: MessageReader<KeyboardInput>,
+    input_focus: Res<InputFocus>,
+    mut text_input_query: Query<&mut TextInput>,
+) {
+    let Some(entity) = input_focus.0 else {
+        keyboard_inputs.clear();
+        return;
+    };
+    let Ok(mut text_input) = text_input_query.get_mut(entity) else {
+        keyboard_inputs.clear();
+        return;
+    };
+    let mut changed = false;
+    for input in keyboard_inputs.read() {
+        if input.state != ButtonState::Pressed {
+            continue;
+        }
+        if input.logical_key == Key::Backspace {
+            changed |= text_input.value.pop().is_some();
+            continue;
+        }
+        let Some(text) = &input.text else {
+            continue;
+        };
+        for character in text.chars().filter(|character| !character.is_control()) {
+            if text_input.value.chars().count() < text_input.max_length {
+                text_input.value.push(character);
+                changed = true;
+            }
+        }
+    }
+    if changed {
+        commands.trigger(ValueChanged {
+            entity,
+            value: text_input.value.clone(),
+        });
+    }
+}
+
+fn update_text_inputs(
+    time: Res<Time>,
+    input_focus: Res<InputFocus>,
+    text_input_query: Query<(Entity, &TextInput, &Children)>,
+    mut text_query: Query<&mut Text>,
+) {
+    // Blink the caret twice a second in the focused field.
+    let caret_visible = time.elapsed_secs().fract() < 0.5;
+    for (entity, text_input, children) in &text_input_query {
+        let caret = if input_focus.0 == Some(entity) && caret_visible {
+            "|"
+        } else {
+            ""
+        };
+        let label = format!("{}{caret}", text_input.value);
+        let mut texts = text_query.iter_many_mut(children);
+        while let Some(mut text) = texts.fetch_next() {
+            if text.0 != label {
+                text.0 = label.clone();
+            }
+        }
+    }
+}
+
+/// A column of content that scrolls when it is taller than `height`.
+///
+/// The list scrolls with the mouse wheel, Page Up and Page Down, or a
+/// gamepad's right stick, and follows focus to any widget inside it.
+pub fn scroll_list(
+    name: impl Into<Cow<'static, str>>,
+    height: Val,
+    content: impl Bundle,
+) -> impl Bundle {
+    (
+        Name::new(name),
+        ScrollList,
+        Node {
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            max_height: height,
+            overflow: Overflow::scroll_y(),
+            ..default()
+        },
+        ScrollPosition::default(),
+        children![content],
+    )
+}
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct ScrollList;
+
+impl ScrollList {
+    /// Logical pixels scrolled per mouse wheel line.
+    const LINE_HEIGHT: f32 = 40.0;
+    /// Logical pixels scrolled per second with the right stick or a page key held.
+    const SCROLL_SPEED: f32 = 600.0;
+}
+
+/// Scroll by `delta` logical pixels, without going past either end of the content.
+fn scroll_by(scroll_position: &mut ScrollPosition, computed: &ComputedNode, delta: f32) {
+    let max =
+        (computed.content_size().y - computed.size().y).max(0.0) * computed.inverse_scale_factor();
+    let y = (scroll_position.y + delta).clamp(0.0, max);
+    if scroll_position.y != y {
+        scroll_position.y = y;
+    }
+}
+
+fn scroll_on_wheel(
+    scroll: On<Pointer<Scroll>>,
+    mut list_query: Query<(&mut ScrollPosition, &ComputedNode), With<ScrollList>>,
+) {
+    let Ok((mut scroll_position, computed)) = list_query.get_mut(scroll.event_target()) else {
+        return;
+    };
+    let delta = match scroll.unit {
+        MouseScrollUnit::Line => scroll.y * ScrollList::LINE_HEIGHT,
+        MouseScrollUnit::Pixel => scroll.y,
+    };
+    scroll_by(&mut scroll_position, computed, -delta);
+}
+
+fn scroll_with_input(
+    time: Res<Time>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    gamepad_query: Query<&Gamepad>,
+    mut list_query: Query<
+        (&mut ScrollPosition, &ComputedNode, &InheritedVisibility),
+        With<ScrollList>,
+    >,
+) {
+    let mut direction = gamepad_query
+        .iter()
+        .map(|gamepad| -gamepad.right_stick().y)
+        .find(|y| y.abs() > 0.2)
+        .unwrap_or_default();
+    if keyboard.pressed(KeyCode::PageDown) {
+        direction += 1.0;
+    }
+    if keyboard.pressed(KeyCode::PageUp) {
+        direction -= 1.0;
+    }
+    if direction == 0.0 {
+        return;
+    }
+    let delta = direction * ScrollList::SCROLL_SPEED * time.delta_secs();
+    for (mut scroll_position, computed, visibility) in &mut list_query {
+        if visibility.get() {
+            scroll_by(&mut scroll_position, computed, delta);
+        }
+    }
+}
+
+/// Keep a focused widget inside a scroll list in view.
+fn scroll_to_focus(
+    input_focus: Res<InputFocus>,
+    parent_query: Query<&ChildOf>,
+    node_query: Query<(&ComputedNode, &UiGlobalTransform)>,
+    mut list_query: Query<
+        (&mut ScrollPosition, &ComputedNode, &UiGlobalTransform),
+        With<ScrollList>,
+    >,
+) {
+    if !input_focus.is_changed() {
+        return;
+    }
+    let Some(focus) = input_focus.0 else {
+        return;
+    };
+    let Some(list) = parent_query
+        .iter_ancestors(focus)
+        .find(|&ancestor| list_query.contains(ancestor))
+    else {
+        return;
+    };
+    let Ok((focus_node, focus_transform)) = node_query.get(focus) else {
+        return;
+    };
+    let Ok((mut scroll_position, list_node, list_transform)) = list_query.get_mut(list) else {
+        return;
+    };
+    // Both are measured in physical pixels from their centers.
+    let focus_top = focus_transform.translation.y - focus_node.size().y / 2.0;
+    let focus_bottom = focus_transform.translation.y + focus_node.size().y / 2.0;
+    let list_top = list_transform.translation.y - list_node.size().y / 2.0;
+    let list_bottom = list_transform.translation.y + list_node.size().y / 2.0;
+    let delta = if focus_top < list_top {
+        focus_top - list_top
+    } else if focus_bottom > list_bottom {
+        focus_bottom - list_bottom
+    } else {
+        return;
+    };
+    scroll_by(
+        &mut scroll_position,
+        list_node,
+        delta * list_node.inverse_scale_factor(),
+    );
+}
@@ -0,0 +1,297 @@
+//! On-screen controls for touch screens.
+//!
+//! A virtual joystick in the bottom left moves the goose and buttons in the
+//! bottom right jump, honk and eat. Every finger is tracked on its own, so the
+//! goose can run, jump and honk at the same time. The controls feed the
+//! [`VirtualInput`] and only show up once the screen has been touched, so they
+//! stay out of the way on desktop.
+
+use bevy::{input::InputSystems, prelude::*, window::PrimaryWindow};
+
+use crate::{
+    Pause,
+    demo::cutscene::Cutscene,
+    input::{Action, ActionSystems, VirtualInput},
+    localization::Localized,
+    screens::Screen,
+    viewport::MainCamera,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<TouchControlsActive>();
+    app.add_systems(OnEnter(Screen::Gameplay), spawn_touch_controls);
+    app.add_systems(
+        PreUpdate,
+        (detect_touch_screen, read_touch_controls)
+            .chain()
+            .after(InputSystems)
+            .before(ActionSystems),
+    );
+    app.add_systems(
+        Update,
+        (update_touch_controls_visibility, update_touch_visuals).run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+/// Whether the player is using a touch screen.
+///
+/// Set when the screen is touched and cleared again by keyboard or gamepad input.
+#[derive(Resource, Reflect, Debug, Default)]
+#[reflect(Resource)]
+pub struct TouchControlsActive(pub bool);
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct TouchControls;
+
+/// The base of the virtual joystick.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct Joystick;
+
+impl Joystick {
+    /// How far a finger has to move from where it landed to push the stick all the way.
+    const RADIUS: f32 = 48.0;
+}
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct JoystickKnob;
+
+/// An on-screen button holding its actions while touched.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct TouchButton(Vec<Action>);
+
+const TOUCH_CONTROL_BACKGROUND: Color = Color::srgba(1.0, 1.0, 1.0, 0.15);
+const TOUCH_CONTROL_PRESSED: Color = Color::srgba(1.0, 1.0, 1.0, 0.4);
+const TOUCH_CONTROL_BORDER: Color = Color::srgba(1.0, 1.0, 1.0, 0.5);
+
+fn spawn_touch_controls(mut commands: Commands) {
+    commands.spawn((
+        Name::new("Touch Controls"),
+        TouchControls,
+        Node {
+            position_type: PositionType::Absolute,
+            width: percent(100),
+            height: percent(100),
+            ..default()
+        },
+        Visibility::Hidden,
+        Pickable::IGNORE,
+        DespawnOnExit(Screen::Gameplay),
+        children![
+            (
+                Name::new("Joystick"),
+                Joystick,
+                Node {
+                    position_type: PositionType::Absolute,
+                    left: px(48),
+                    bottom: px(48),
+                    width: px(Joystick::RADIUS * 3.0),
+                    height: px(Joystick::RADIUS * 3.0),
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    border: UiRect::all(px(3)),
+                    border_radius: BorderRadius::MAX,
+                    ..default()
+                },
+                BackgroundColor(TOUCH_CONTROL_BACKGROUND),
+                BorderColor::all(TOUCH_CONTROL_BORDER),
+                Pickable::IGNORE,
+                children![(
+                    Name::new("Joystick Knob"),
+                    JoystickKnob,
+                    Node {
+                        width: px(Joystick::RADIUS),
+                        height: px(Joystick::RADIUS),
+                        border_radius: BorderRadius::MAX,
+                        ..default()
+                    },
+                    BackgroundColor(TOUCH_CONTROL_PRESSED),
+                    Pickable::IGNORE,
+                )],
+            ),
+            touch_button(
+                "Jump",
+                vec![Action::Jump, Action::Glide],
+                96.0,
+                Node {
+                    right: px(48),
+                    bottom: px(48),
+                    ..default()
+                },
+            ),
+            touch_button(
+                "Honk",
+                vec![Action::Shoot],
+                72.0,
+                Node {
+                    right: px(160),
+                    bottom: px(64),
+                    ..default()
+                },
+            ),
+            touch_button(
+                "Eat",
+                vec![Action::Eat],
+                64.0,
+                Node {
+                    right: px(72),
+                    bottom: px(160),
+                    ..default()
+                },
+            ),
+            touch_button(
+                "II",
+                vec![Action::Pause],
+                48.0,
+                Node {
+                    left: percent(50),
+                    top: px(16),
+                    margin: UiRect::left(px(-24)),
+                    ..default()
+                },
+            ),
+        ],
+    ));
+}
+
+/// A round button of the given diameter, placed by the `position` node's insets.
+fn touch_button(label: &str, actions: Vec<Action>, size: f32, position: Node) -> impl Bundle {
+    (
+        Name::new(format!("Touch Button {label}")),
+        TouchButton(actions),
+        Node {
+            position_type: PositionType::Absolute,
+            width: px(size),
+            height: px(size),
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
+            border: UiRect::all(px(3)),
+            border_radius: BorderRadius::MAX,
+            ..position
+        },
+        BackgroundColor(TOUCH_CONTROL_BACKGROUND),
+        BorderColor::all(TOUCH_CONTROL_BORDER),
+        Pickable::IGNORE,
+        children![(
+            Text::new(label),
+            Localized::new(label),
+            TextFont::from_font_size(size * 0.3),
+            TextColor(TOUCH_CONTROL_BORDER),
+            Pickable::IGNORE,
+        )],
+    )
+}
+
+fn detect_touch_screen(
+    touches: Res<Touches>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    gamepad_query: Query<&Gamepad>,
+    mut active: ResMut<TouchControlsActive>,
+) {
+    if touches.any_just_pressed() {
+        active.0 = true;
+    } else if keyboard.get_just_pressed().len() > 0
+        || gamepad_query
+            .iter()
+            .any(|gamepad| gamepad.get_just_pressed().next().is_some())
+    {
+        active.0 = false;
+    }
+}
+
+fn read_touch_controls(
+    touches: Res<Touches>,
+    window: Single<&Window, With<PrimaryWindow>>,
+    camera_query: Query<&Camera, With<MainCamera>>,
+    ui_scale: Res<UiScale>,
+    controls_query: Query<&InheritedVisibility, With<TouchControls>>,
+    button_query: Query<(&TouchButton, &ComputedNode, &UiGlobalTransform)>,
+    mut joystick_touch: Local<Option<u64>>,
+    mut virtual_input: ResMut<VirtualInput>,
+) {
+    virtual_input.pressed.clear();
+    virtual_input.horizontal = 0.0;
+    if !controls_query.iter().any(|visibility| visibility.get()) {
+        *joystick_touch = None;
+        return;
+    }
+
+    // UI nodes are laid out in physical pixels inside the letterboxed viewport,
+    // touches are reported in logical pixels relative to the whole window.
+    let viewport_position = camera_query
+        .single()
+        .ok()
+        .and_then(|camera| camera.viewport.as_ref())
+        .map_or(Vec2::ZERO, |viewport| viewport.physical_position.as_vec2());
+    let button_at = |touch: &bevy::input::touch::Touch| {
+        let point = touch.position() * window.scale_factor() - viewport_position;
+        button_query
+            .iter()
+            .find(|(_, computed, transform)| computed.contains_point(**transform, point))
+            .map(|(button, _, _)| button)
+    };
+
+    if joystick_touch.is_some_and(|id| touches.get_pressed(id).is_none()) {
+        *joystick_touch = None;
+    }
+    if joystick_touch.is_none() {
+        // A new finger on the left half of the screen takes the joystick.
+        *joystick_touch = touches
+            .iter_just_pressed()
+            .find(|touch| touch.position().x < window.width() / 2.0 && button_at(touch).is_none())
+            .map(|touch| touch.id());
+    }
+
+    for touch in touches.iter() {
+        if Some(touch.id()) == *joystick_touch {
+            let offset = touch.position().x - touch.start_position().x;
+            virtual_input.horizontal = (offset / (Joystick::RADIUS * ui_scale.0)).clamp(-1.0, 1.0);
+        } else if let Some(button) = button_at(touch) {
+            virtual_input.pressed.extend(button.0.iter().copied());
+        }
+    }
+}
+
+fn update_touch_controls_visibility(
+    active: Res<TouchControlsActive>,
+    pause: Res<State<Pause>>,
+    cutscene: Res<State<Cutscene>>,
+    mut controls_query: Query<&mut Visibility, With<TouchControls>>,
+) {
+    let visibility = if active.0 && !pause.get().0 && !cutscene.get().0 {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    };
+    for mut controls_visibility in &mut controls_query {
+        controls_visibility.set_if_neq(visibility);
+    }
+}
+
+fn update_touch_visuals(
+    virtual_input: Res<VirtualInput>,
+    mut button_query: Query<(&TouchButton, &mut BackgroundColor)>,
+    mut knob_query: Query<&mut UiTransform, With<JoystickKnob>>,
+) {
+    for (button, mut background) in &mut button_query {
+        let pressed = button
+            .0
+            .iter()
+            .any(|action| virtual_input.pressed.contains(action));
+        let color = if pressed {
+            TOUCH_CONTROL_PRESSED
+        } else {
+            TOUCH_CONTROL_BACKGROUND
+        };
+        background.set_if_neq(BackgroundColor(color));
+    }
+    for mut transform in &mut knob_query {
+        let translation = Val2::px(virtual_input.horizontal * Joystick::RADIUS, 0.0);
+        if transform.translation != translation {
+            transform.translation = translation;
+        }
+    }
+}
@@ -0,0 +1,112 @@
+//! A fixed virtual resolution for the game view.
+//!
+//! The [`MainCamera`] always shows [`VirtualResolution`] world units, however
+//! big the window or canvas is, so no player sees more of the level than
+//! another. Its viewport is the largest area of that aspect ratio fitting in
+//! the window, and a second camera clears the bars left around it. With
+//! [`Settings::pixel_perfect`] the viewport is scaled by whole numbers only,
+//! keeping the nearest-sampled pixel art crisp.
+
+use bevy::{
+    camera::{ScalingMode, Viewport, visibility::RenderLayers},
+    prelude::*,
+    window::{PrimaryWindow, WindowResized},
+};
+
+use crate::settings::Settings;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<VirtualResolution>();
+    app.add_systems(Startup, spawn_letterbox_camera);
+    app.add_systems(
+        PostUpdate,
+        fit_viewport_to_window.run_if(
+            resource_changed::<VirtualResolution>
+                .or(resource_changed::<Settings>)
+                .or(on_message::<WindowResized>),
+        ),
+    );
+}
+
+/// Size of the game view in world units.
+#[derive(Resource, Reflect, Debug, Clone, Copy, PartialEq)]
+#[reflect(Resource)]
+pub struct VirtualResolution(pub UVec2);
+
+impl Default for VirtualResolution {
+    fn default() -> Self {
+        Self(UVec2::new(1280, 720))
+    }
+}
+
+impl VirtualResolution {
+    /// A projection showing exactly this resolution.
+    pub fn projection(self) -> Projection {
+        Projection::Orthographic(OrthographicProjection {
+            scaling_mode: ScalingMode::Fixed {
+                width: self.0.x as f32,
+                height: self.0.y as f32,
+            },
+            ..OrthographicProjection::default_2d()
+        })
+    }
+}
+
+/// The camera rendering the game and its UI.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct MainCamera;
+
+/// Renders nothing, only clearing the letterbox bars around the main camera's viewport.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct LetterboxCamera;
+
+fn spawn_letterbox_camera(mut commands: Commands) {
+    commands.spawn((
+        Name::new("Letterbox Camera"),
+        LetterboxCamera,
+        Camera2d,
+        Camera {
+            order: -1,
+            clear_color: ClearColorConfig::Custom(Color::BLACK),
+            ..default()
+        },
+        // Keep the world out of this camera's view.
+        RenderLayers::none(),
+    ));
+}
+
+fn fit_viewport_to_window(
+    resolution: Res<VirtualResolution>,
+    settings: Res<Settings>,
+    window: Single<&Window, With<PrimaryWindow>>,
+    mut camera_query: Query<(&mut Camera, &mut Projection), With<MainCamera>>,
+) {
+    let window_size = window.physical_size();
+    let virtual_size = resolution.0.as_vec2();
+    let mut scale = (window_size.as_vec2() / virtual_size).min_element();
+    // Windows smaller than the virtual resolution can only scale down smoothly.
+    if settings.pixel_perfect && scale >= 1.0 {
+        scale = scale.floor();
+    }
+    let size = (virtual_size * scale)
+        .round()
+        .as_uvec2()
+        .clamp(UVec2::ONE, window_size.max(UVec2::ONE));
+    let viewport = Viewport {
+        physical_position: (window_size.saturating_sub(size)) / 2,
+        physical_size: size,
+        ..default()
+    };
+
+    for (mut camera, mut projection) in &mut camera_query {
+        camera.viewport = Some(viewport.clone());
+        if let Projection::Orthographic(orthographic) = &mut *projection {
+            orthographic.scaling_mode = ScalingMode::Fixed {
+                width: virtual_size.x,
+                height: virtual_size.y,
+            };
+        }
+    }
+}